@@ -0,0 +1,74 @@
+use std::hint::black_box;
+
+use chess::gamelogic::{coordinates::Position, game::Game, moves::MoveRequest, pieces::Color};
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+
+/// Plays the given moves (e.g. `"E2E4"`) starting from the initial position.
+fn play(moves: &[&str]) -> Game {
+    moves.iter().fold(Game::new(), |game, mov| {
+        let req = MoveRequest::new(
+            Position::from_str(&mov[0..2]),
+            Position::from_str(&mov[2..4]),
+            None,
+        );
+        game.perform_move_request(req)
+            .unwrap_or_else(|| panic!("{mov} is not a valid move"))
+    })
+}
+
+/// Italian game with both sides ready to castle.
+fn middlegame() -> Game {
+    play(&[
+        "E2E4", "E7E5", "G1F3", "B8C6", "F1C4", "F8C5", "D2D3", "G8F6",
+    ])
+}
+
+fn positions() -> Vec<(&'static str, Game)> {
+    vec![("initial", Game::new()), ("middlegame", middlegame())]
+}
+
+fn move_generation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("legal_moves");
+    for (name, game) in positions() {
+        group.bench_with_input(BenchmarkId::from_parameter(name), &game, |b, game| {
+            b.iter(|| black_box(game).legal_moves())
+        });
+    }
+    group.finish();
+}
+
+fn king_in_check(c: &mut Criterion) {
+    let mut group = c.benchmark_group("is_king_in_check");
+    for (name, game) in positions() {
+        group.bench_with_input(BenchmarkId::from_parameter(name), &game, |b, game| {
+            b.iter(|| black_box(game).is_king_in_check(Color::White))
+        });
+    }
+    group.finish();
+}
+
+fn perform_move(c: &mut Criterion) {
+    let mut group = c.benchmark_group("perform_move");
+    for (name, game) in positions() {
+        let mov = game.legal_moves()[0];
+        group.bench_with_input(BenchmarkId::from_parameter(name), &game, |b, game| {
+            b.iter(|| black_box(game).perform_move(black_box(mov)))
+        });
+    }
+    group.finish();
+}
+
+fn perft(c: &mut Criterion) {
+    let mut group = c.benchmark_group("perft");
+    group.sample_size(10);
+    for depth in 1..=3 {
+        let game = Game::new();
+        group.bench_with_input(BenchmarkId::new("initial", depth), &depth, |b, &depth| {
+            b.iter(|| game.perft(black_box(depth)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, move_generation, king_in_check, perform_move, perft);
+criterion_main!(benches);
@@ -0,0 +1,138 @@
+use bevy::{
+    asset::{AssetApp, io::AssetSourceBuilder},
+    prelude::*,
+};
+use std::{
+    collections::HashMap,
+    f32::consts::PI,
+    path::{Path, PathBuf},
+};
+
+/// Name of the asset source under which the packs directory is registered.
+const PACK_SOURCE: &str = "packs";
+/// File in a pack listing the orientations of its models.
+const ORIENTATIONS_FILE: &str = "orientations.txt";
+
+/// Alternative asset packs provided by the user.
+///
+/// Every sub-directory of the packs directory is a pack. A pack may replace any of the built-in
+/// GLB files (e.g. `king_white.glb`, `board.glb`) by containing a file of the same name. Files
+/// missing in a pack fall back to the built-in assets.
+///
+/// Models of a pack are placed as they are, unless the pack lists a rotation around the vertical
+/// axis for them in `orientations.txt`, one `<file> <degrees>` pair per line. This way, e.g.
+/// knights of both colors can be turned to face their opponent.
+#[derive(Resource, Debug, Default)]
+pub struct AssetPacks {
+    /// The packs directory, if it exists
+    dir: Option<PathBuf>,
+    /// Names of the available packs, sorted alphabetically
+    available: Vec<String>,
+    /// Rotation offsets of the models of each pack, in radians, parallel to `available`
+    orientations: Vec<HashMap<String, f32>>,
+    /// Index into `available` of the pack in use. None means the built-in assets.
+    active: Option<usize>,
+}
+
+impl AssetPacks {
+    /// Scans the packs directory (`$XDG_DATA_HOME/chess/packs` or `~/.local/share/chess/packs`).
+    pub fn scan() -> Self {
+        let Some(dir) = packs_dir().filter(|dir| dir.is_dir()) else {
+            return Self::default();
+        };
+        let mut available: Vec<String> = std::fs::read_dir(&dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .collect();
+        available.sort();
+        let orientations = available
+            .iter()
+            .map(|pack| read_orientations(&dir.join(pack).join(ORIENTATIONS_FILE)))
+            .collect();
+        Self {
+            dir: Some(dir),
+            available,
+            orientations,
+            active: None,
+        }
+    }
+
+    /// Registers the packs directory as asset source. Has to happen before the `AssetPlugin` is
+    /// added.
+    pub fn register_source(&self, app: &mut App) {
+        if let Some(dir) = self.dir.as_ref().and_then(|dir| dir.to_str()) {
+            app.register_asset_source(PACK_SOURCE, AssetSourceBuilder::platform_default(dir, None));
+        }
+    }
+
+    /// Name of the active pack, None if the built-in assets are used.
+    pub fn active_name(&self) -> Option<&str> {
+        self.active.map(|index| self.available[index].as_str())
+    }
+
+    /// Switches to the next pack. After the last pack, the built-in assets are used again.
+    pub fn cycle(&mut self) {
+        self.active = match self.active {
+            None if !self.available.is_empty() => Some(0),
+            Some(index) if index + 1 < self.available.len() => Some(index + 1),
+            _ => None,
+        };
+    }
+
+    /// Asset path of the first scene in `file`, taken from the active pack if it provides the file.
+    pub fn scene(&self, file: &str) -> String {
+        match (&self.dir, self.active_name()) {
+            (Some(dir), Some(pack)) if dir.join(pack).join(file).is_file() => {
+                format!("{PACK_SOURCE}://{pack}/{file}#Scene0")
+            }
+            _ => format!("{file}#Scene0"),
+        }
+    }
+
+    /// Rotation around the vertical axis that turns the model in `file` to its intended facing.
+    pub fn orientation(&self, file: &str) -> Quat {
+        let angle = match (&self.dir, self.active) {
+            (Some(dir), Some(index)) if dir.join(&self.available[index]).join(file).is_file() => {
+                self.orientations[index].get(file).copied().unwrap_or(0.)
+            }
+            _ => builtin_orientation(file),
+        };
+        Quat::from_axis_angle(Vec3::Y, angle)
+    }
+}
+
+/// Rotation offsets of the built-in models, in radians.
+fn builtin_orientation(file: &str) -> f32 {
+    match file {
+        // both knight models face the black side
+        "knight_black.glb" => PI,
+        _ => 0.,
+    }
+}
+
+/// Reads the orientations file of a pack. Malformed lines are skipped.
+fn read_orientations(path: &Path) -> HashMap<String, f32> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return HashMap::new();
+    };
+    content
+        .lines()
+        .filter_map(|line| {
+            let (file, degrees) = line.trim().split_once(char::is_whitespace)?;
+            let degrees: f32 = degrees.trim().parse().ok()?;
+            Some((file.to_string(), degrees.to_radians()))
+        })
+        .collect()
+}
+
+fn packs_dir() -> Option<PathBuf> {
+    let data_dir = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share"))
+        })?;
+    Some(data_dir.join("chess").join("packs"))
+}
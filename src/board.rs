@@ -0,0 +1,148 @@
+use bevy::prelude::*;
+use std::f32::consts::PI;
+
+use crate::{asset_packs::AssetPacks, gamelogic::coordinates::Position};
+
+/// Side length of a square in the board model, in world units.
+const MODEL_SQUARE_SIZE: f32 = 2.;
+
+/// Placement of the chessboard in the world. Files run along +X, ranks along -Z, and the corner of
+/// A1 facing away from H8 lies at `origin`. To place the board elsewhere, insert this resource
+/// before the plugins are added; the models, the camera and the light follow it.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct BoardLayout {
+    pub origin: Vec3,
+    pub square_size: f32,
+}
+
+impl Default for BoardLayout {
+    fn default() -> Self {
+        Self {
+            origin: Vec3::ZERO,
+            square_size: MODEL_SQUARE_SIZE,
+        }
+    }
+}
+
+impl BoardLayout {
+    /// Center of the square at `pos` on the board surface.
+    pub fn to_world(&self, pos: Position) -> Vec3 {
+        self.origin
+            + Vec3::new(
+                (pos.x as f32 + 0.5) * self.square_size,
+                0.,
+                -(pos.y as f32 + 0.5) * self.square_size,
+            )
+    }
+
+    /// Square below the world position `point`, ignoring its height. None if it is not above the
+    /// board.
+    pub fn from_world(&self, point: Vec3) -> Option<Position> {
+        let local = (point - self.origin) / self.square_size;
+        let (x, y) = (local.x.floor(), (-local.z).floor());
+        if (0. ..8.).contains(&x) && (0. ..8.).contains(&y) {
+            Some(Position::new(x as u8, y as u8))
+        } else {
+            None
+        }
+    }
+
+    /// Center of the board surface.
+    pub fn center(&self) -> Vec3 {
+        self.origin + Vec3::new(4., 0., -4.) * self.square_size
+    }
+
+    /// Scale of the models relative to the size they were made for.
+    pub fn model_scale(&self) -> f32 {
+        self.square_size / MODEL_SQUARE_SIZE
+    }
+}
+
+/// Root entity of the chessboard, parent of the board model and of one entity per square.
+#[derive(Component)]
+pub struct Board {
+    /// Square entities, indexed by `[x][y]`
+    squares: [[Entity; 8]; 8],
+}
+
+impl Board {
+    /// The entity of the square at `pos`. Highlights and markers are spawned as its children.
+    pub fn square(&self, pos: Position) -> Entity {
+        self.squares[pos.x as usize][pos.y as usize]
+    }
+}
+
+/// A square of the chessboard. Its transform is the center of the square on the board surface.
+#[derive(Component)]
+pub struct Square {
+    pub pos: Position,
+}
+
+#[derive(Component)]
+pub struct BoardModel {}
+
+/// Model of the table the board stands on.
+#[derive(Component)]
+pub struct TableModel {}
+
+/// Shows and hides the table under the board on key press.
+pub fn toggle_table(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    asset_packs: Res<AssetPacks>,
+    layout: Res<BoardLayout>,
+    tables: Query<Entity, With<TableModel>>,
+) {
+    if !keys.just_pressed(KeyCode::KeyT) {
+        return;
+    }
+    if tables.is_empty() {
+        commands.spawn((
+            SceneRoot(asset_server.load(asset_packs.scene("table.glb"))),
+            Transform::from_translation(layout.center())
+                .with_scale(Vec3::splat(layout.model_scale())),
+            TableModel {},
+        ));
+    }
+    for entity in &tables {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Spawns the board with its model and its squares.
+pub fn spawn_board(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    asset_packs: &AssetPacks,
+    layout: &BoardLayout,
+) {
+    let board = commands
+        .spawn((Transform::default(), Visibility::default()))
+        .id();
+
+    commands.spawn((
+        SceneRoot(asset_server.load(asset_packs.scene("board.glb"))),
+        Transform::from_translation(layout.center())
+            .with_rotation(Quat::from_axis_angle(Vec3::Y, PI * 0.5))
+            .with_scale(Vec3::splat(layout.model_scale())),
+        BoardModel {},
+        ChildOf(board),
+    ));
+
+    let squares = std::array::from_fn(|x| {
+        std::array::from_fn(|y| {
+            let pos = Position::new(x as u8, y as u8);
+            commands
+                .spawn((
+                    Square { pos },
+                    Transform::from_translation(layout.to_world(pos))
+                        .with_scale(Vec3::splat(layout.model_scale())),
+                    Visibility::default(),
+                    ChildOf(board),
+                ))
+                .id()
+        })
+    });
+    commands.entity(board).insert(Board { squares });
+}
@@ -0,0 +1,152 @@
+use bevy::{
+    prelude::*,
+    window::{MonitorSelection, WindowMode},
+};
+
+use crate::{board::BoardLayout, tuning::Tuning};
+
+/// Narrowest aspect ratio (width / height) of the window at which the presets show the whole
+/// board. In narrower windows, the camera moves back along its view direction.
+const FRAMED_ASPECT: f32 = 1.;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CameraPreset {
+    White,
+    Black,
+    TopDown,
+    Cinematic,
+}
+
+impl CameraPreset {
+    /// Transform of the preset, looking at the center of the board. Scales with the board.
+    pub fn transform(self, layout: &BoardLayout) -> Transform {
+        // offsets for the board model at its original size
+        let (offset, up) = match self {
+            Self::White => (Vec3::new(0., 20., 16.), Vec3::Y),
+            Self::Black => (Vec3::new(0., 20., -16.), Vec3::Y),
+            Self::TopDown => (Vec3::new(0., 26., 0.), Vec3::NEG_Z),
+            Self::Cinematic => (Vec3::new(-14., 5., 11.), Vec3::Y),
+        };
+        let center = layout.center();
+        Transform::from_translation(center + offset * layout.model_scale()).looking_at(center, up)
+    }
+
+    /// Transform of the preset, moved back far enough to show the whole board in a window with
+    /// the given aspect ratio.
+    pub fn framed(self, layout: &BoardLayout, aspect: f32) -> Transform {
+        let mut transform = self.transform(layout);
+        let distance_factor = (FRAMED_ASPECT / aspect).max(1.);
+        let center = layout.center();
+        transform.translation = center + (transform.translation - center) * distance_factor;
+        transform
+    }
+}
+
+fn aspect(window: &Window) -> f32 {
+    window.width() / window.height().max(1.)
+}
+
+/// Moves the camera smoothly from where it was to the transform of a preset.
+#[derive(Component)]
+pub struct CameraRig {
+    /// The preset moved to, whose framing is updated when the window is resized
+    preset: CameraPreset,
+    start: Transform,
+    target: Transform,
+    /// Progress of the transition from `start` to `target`, in [0, 1]
+    progress: f32,
+}
+
+impl CameraRig {
+    pub fn new(preset: CameraPreset, layout: &BoardLayout) -> Self {
+        let transform = preset.transform(layout);
+        Self {
+            preset,
+            start: transform,
+            target: transform,
+            progress: 1.,
+        }
+    }
+
+    /// Starts a transition from the current camera transform to `target`.
+    pub fn transition_to(&mut self, current: Transform, target: Transform) {
+        self.start = current;
+        self.target = target;
+        self.progress = 0.;
+    }
+}
+
+pub fn select_camera_preset(
+    keys: Res<ButtonInput<KeyCode>>,
+    window: Single<&Window>,
+    layout: Res<BoardLayout>,
+    mut cameras: Query<(&Transform, &mut CameraRig)>,
+) {
+    let preset = if keys.just_pressed(KeyCode::Digit1) {
+        CameraPreset::White
+    } else if keys.just_pressed(KeyCode::Digit2) {
+        CameraPreset::Black
+    } else if keys.just_pressed(KeyCode::Digit3) {
+        CameraPreset::TopDown
+    } else if keys.just_pressed(KeyCode::Digit4) {
+        CameraPreset::Cinematic
+    } else {
+        return;
+    };
+    for (transform, mut rig) in &mut cameras {
+        rig.preset = preset;
+        rig.transition_to(*transform, preset.framed(&layout, aspect(&window)));
+    }
+}
+
+/// Keeps the whole board in view when the window is resized or the board is moved. A running
+/// transition is redirected, otherwise the camera jumps to the new framing.
+pub fn frame_board(
+    window: Single<Ref<Window>>,
+    layout: Res<BoardLayout>,
+    mut cameras: Query<(&mut Transform, &mut CameraRig)>,
+) {
+    if !window.is_changed() && !layout.is_changed() {
+        return;
+    }
+    for (mut transform, mut rig) in &mut cameras {
+        let target = rig.preset.framed(&layout, aspect(&window));
+        if target == rig.target {
+            continue;
+        }
+        rig.target = target;
+        if rig.progress >= 1. {
+            *transform = target;
+        }
+    }
+}
+
+/// Switches between window and borderless fullscreen on Alt+Enter.
+pub fn toggle_fullscreen(keys: Res<ButtonInput<KeyCode>>, mut window: Single<&mut Window>) {
+    if !keys.any_pressed([KeyCode::AltLeft, KeyCode::AltRight])
+        || !keys.just_pressed(KeyCode::Enter)
+    {
+        return;
+    }
+    window.mode = match window.mode {
+        WindowMode::Windowed => WindowMode::BorderlessFullscreen(MonitorSelection::Current),
+        _ => WindowMode::Windowed,
+    };
+}
+
+pub fn animate_camera(
+    mut cameras: Query<(&mut Transform, &mut CameraRig)>,
+    tuning: Res<Tuning>,
+    time: Res<Time>,
+) {
+    for (mut transform, mut rig) in &mut cameras {
+        if rig.progress >= 1. {
+            continue;
+        }
+        rig.progress = (rig.progress + time.delta_secs() / tuning.camera_transition).min(1.);
+        // ease in and out
+        let t = rig.progress * rig.progress * (3. - 2. * rig.progress);
+        transform.translation = rig.start.translation.lerp(rig.target.translation, t);
+        transform.rotation = rig.start.rotation.slerp(rig.target.rotation, t);
+    }
+}
@@ -0,0 +1,84 @@
+use bevy::prelude::*;
+use std::f32::consts::PI;
+
+use crate::board::BoardLayout;
+
+/// Speed at which knocked over pieces slide over the board, at the default board size. Like
+/// `GRAVITY` and `FALL_DEPTH`, it is scaled by `BoardLayout::model_scale`.
+const SLIDE_SPEED: f32 = 6.;
+/// Angular speed at which knocked over pieces tip over, in radians per second.
+const TIP_SPEED: f32 = 6.;
+const GRAVITY: f32 = 20.;
+/// How far below the board knocked over pieces fall before they are despawned.
+const FALL_DEPTH: f32 = 20.;
+
+/// What happens to the model of a captured piece.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureStyle {
+    /// The piece disappears immediately
+    #[default]
+    Vanish,
+    /// The piece is knocked over, slides off the board and falls down
+    KnockOver,
+}
+
+/// Captured piece tumbling off the board. Despawned once it has fallen out of view.
+#[derive(Component)]
+pub struct KnockedOver {
+    /// Velocity at the default board size, scaled by the layout when moving the piece
+    velocity: Vec3,
+    tip_axis: Dir3,
+    /// Angle by which the piece has tipped over so far
+    tipped: f32,
+}
+
+impl KnockedOver {
+    /// Knocks the piece over in the horizontal component of `direction`.
+    pub fn new(direction: Vec3) -> Self {
+        let direction = Vec3::new(direction.x, 0., direction.z)
+            .try_normalize()
+            .unwrap_or(Vec3::Z);
+        Self {
+            velocity: direction * SLIDE_SPEED,
+            tip_axis: Dir3::new(Vec3::Y.cross(direction)).unwrap_or(Dir3::X),
+            tipped: 0.,
+        }
+    }
+}
+
+pub fn switch_capture_style(keys: Res<ButtonInput<KeyCode>>, mut style: ResMut<CaptureStyle>) {
+    if !keys.just_pressed(KeyCode::KeyK) {
+        return;
+    }
+    *style = match *style {
+        CaptureStyle::Vanish => CaptureStyle::KnockOver,
+        CaptureStyle::KnockOver => CaptureStyle::Vanish,
+    };
+    info!("Capture style: {:?}", *style);
+}
+
+pub fn animate_knocked_over(
+    mut commands: Commands,
+    mut pieces: Query<(Entity, &mut Transform, &mut KnockedOver)>,
+    layout: Res<BoardLayout>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_secs();
+    let scale = layout.model_scale();
+    for (entity, mut transform, mut knocked_over) in &mut pieces {
+        if knocked_over.tipped < PI * 0.5 {
+            let step = (TIP_SPEED * dt).min(PI * 0.5 - knocked_over.tipped);
+            knocked_over.tipped += step;
+            transform.rotate_axis(knocked_over.tip_axis, step);
+        }
+
+        if layout.from_world(transform.translation).is_none() {
+            knocked_over.velocity.y -= GRAVITY * dt;
+        }
+        transform.translation += knocked_over.velocity * scale * dt;
+
+        if transform.translation.y < layout.origin.y - FALL_DEPTH * scale {
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use bevy::{
+    input::keyboard::{Key, KeyboardInput},
+    prelude::*,
+};
+
+use crate::{
+    ChessGame, GameReplacedEvent, SelectionChangedEvent, TryMoveEvent,
+    gamelogic::moves::MoveRequest, input::BlocksBoardInput, log_buffer::LogBuffer,
+};
+
+/// Number of output lines shown above the input line.
+const VISIBLE_LINES: usize = 16;
+
+/// Deepest perft the console runs. It blocks the frame, and depth 4 already takes seconds in debug
+/// builds.
+const MAX_PERFT_DEPTH: u32 = 3;
+
+/// Number of log messages the `log` command shows.
+const LOG_LINES: usize = 12;
+
+const HELP: &str = "Commands: move e2e4, perft <depth>, eval, undo, log, help";
+
+/// Text of the developer console, which is opened with the backtick key.
+#[derive(Resource, Debug, Default)]
+pub struct Console {
+    /// The command being typed
+    pub input: String,
+    /// Entered commands and their replies, oldest first
+    pub output: Vec<String>,
+}
+
+impl Console {
+    fn text(&self) -> String {
+        let skipped = self.output.len().saturating_sub(VISIBLE_LINES);
+        let mut text: String = self.output[skipped..]
+            .iter()
+            .map(|line| format!("{line}\n"))
+            .collect();
+        text.push_str("> ");
+        text.push_str(&self.input);
+        text
+    }
+}
+
+#[derive(Component)]
+pub struct ConsolePanel {}
+
+#[derive(Component)]
+pub struct ConsoleText {}
+
+/// A command was entered into the console.
+#[derive(Event)]
+pub struct ConsoleCommandEvent {
+    pub line: String,
+}
+
+/// Opens and closes the console on backtick (Esc also closes it) and edits the command line while
+/// it is open. Runs before `Update`, so the shortcuts of the game do not see the typed keys.
+pub fn console_input(
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    mut typed: MessageReader<KeyboardInput>,
+    mut console: ResMut<Console>,
+    panels: Query<Entity, With<ConsolePanel>>,
+    mut commands: Commands,
+) {
+    let open = !panels.is_empty();
+    if keys.just_pressed(KeyCode::Backquote) || (open && keys.just_pressed(KeyCode::Escape)) {
+        if open {
+            for entity in &panels {
+                commands.entity(entity).despawn();
+            }
+        } else {
+            spawn_console(&mut commands, &console);
+        }
+        typed.clear();
+        keys.reset_all();
+        return;
+    }
+    if !open {
+        return;
+    }
+
+    for input in typed.read() {
+        if !input.state.is_pressed() {
+            continue;
+        }
+        match &input.logical_key {
+            Key::Enter => {
+                let line = std::mem::take(&mut console.input);
+                commands.trigger(ConsoleCommandEvent { line });
+            }
+            Key::Backspace => {
+                console.input.pop();
+            }
+            _ => {
+                if let Some(text) = &input.text {
+                    console
+                        .input
+                        .extend(text.chars().filter(|c| !c.is_control()));
+                }
+            }
+        }
+    }
+    keys.reset_all();
+}
+
+fn spawn_console(commands: &mut Commands, console: &Console) {
+    commands.spawn((
+        ConsolePanel {},
+        BlocksBoardInput {},
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(0.),
+            width: Val::Percent(100.),
+            padding: UiRect::all(Val::Px(8.)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0., 0., 0., 0.8)),
+        children![(
+            ConsoleText {},
+            Text::new(console.text()),
+            TextFont {
+                font_size: 16.,
+                ..default()
+            },
+        )],
+    ));
+}
+
+pub fn update_console_text(console: Res<Console>, mut texts: Query<&mut Text, With<ConsoleText>>) {
+    if !console.is_changed() {
+        return;
+    }
+    for mut text in &mut texts {
+        text.0 = console.text();
+    }
+}
+
+/// Runs a console command on the game and prints its reply.
+pub fn run_console_command(
+    event: On<ConsoleCommandEvent>,
+    mut console: ResMut<Console>,
+    mut game: ResMut<ChessGame>,
+    log: Res<LogBuffer>,
+    mut commands: Commands,
+) {
+    let words: Vec<&str> = event.line.split_whitespace().collect();
+    if words.is_empty() {
+        return;
+    }
+    console.output.push(format!("> {}", event.line));
+
+    let reply = match words.as_slice() {
+        ["move", name] => match MoveRequest::parse(name, game.game.active_color()) {
+            Some(move_req) => match move_req.check(&game.game) {
+                Ok(_) => {
+                    commands.trigger(TryMoveEvent {
+                        origin: move_req.origin,
+                        destination: move_req.destination,
+                        promotion: move_req.promotion,
+                    });
+                    game.select(None);
+                    commands.trigger(SelectionChangedEvent {});
+                    format!("Played {}", name)
+                }
+                Err(error) => format!("Illegal move: {}", error),
+            },
+            None => format!("Not a move: {}", name),
+        },
+        ["perft", depth] => match depth.parse() {
+            Ok(depth) if depth > MAX_PERFT_DEPTH => {
+                format!(
+                    "Depth {} would take too long, at most {}",
+                    depth, MAX_PERFT_DEPTH
+                )
+            }
+            Ok(depth) => format!("{} positions", game.game.perft(depth)),
+            Err(_) => format!("Not a depth: {}", depth),
+        },
+        ["eval"] => {
+            let imbalance = game.game.material_imbalance();
+            format!("{} ({:+})", imbalance, imbalance.balance)
+        }
+        ["undo"] => {
+            if game.take_back() {
+                commands.trigger(GameReplacedEvent {});
+                "Took the last move back".to_string()
+            } else {
+                "No move to take back".to_string()
+            }
+        }
+        ["log"] => {
+            let lines = log.recent(LOG_LINES);
+            if lines.is_empty() {
+                "No log messages".to_string()
+            } else {
+                lines.join("\n")
+            }
+        }
+        ["fen", ..] => "Loading positions from FEN is not supported yet".to_string(),
+        ["help"] => HELP.to_string(),
+        _ => format!("Unknown command. {}", HELP),
+    };
+    // the reply of `log` is already in the log
+    if words != ["log"] {
+        info!("Console: {} -> {}", event.line, reply);
+    }
+    console.output.push(reply);
+}
@@ -0,0 +1,61 @@
+//! Writes the moves and the position of the current game to a file when the game crashes, so bug
+//! reports come with a way to reproduce them.
+
+use std::fs;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use chess::{ChessGame, GameReplacedEvent, SuccessfulMoveEvent};
+
+/// Description of the current game, kept up to date for the panic hook, which cannot access the
+/// world.
+static GAME_DUMP: Mutex<String> = Mutex::new(String::new());
+
+/// Installs the panic hook and keeps the game dump up to date.
+pub struct CrashReportPlugin;
+
+impl Plugin for CrashReportPlugin {
+    fn build(&self, app: &mut App) {
+        let default_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            default_hook(info);
+            let millis = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |time| time.as_millis());
+            let path = std::env::temp_dir().join(format!("chess-crash-{}.txt", millis));
+            // the panic may have happened while the dump was updated
+            let dump = match GAME_DUMP.lock() {
+                Ok(dump) => dump.clone(),
+                Err(poisoned) => poisoned.into_inner().clone(),
+            };
+            match fs::write(&path, format!("{}\n\n{}", info, dump)) {
+                Ok(()) => eprintln!(
+                    "The game crashed. Its moves and position were saved to {}, please attach \
+                     this file to the bug report.",
+                    path.display()
+                ),
+                Err(err) => eprintln!("Could not save the game to {}: {}", path.display(), err),
+            }
+        }));
+        app.add_systems(Startup, |game: Res<ChessGame>| update_dump(&game))
+            .add_observer(update_dump_on_move)
+            .add_observer(update_dump_on_replace);
+    }
+}
+
+fn update_dump(game: &ChessGame) {
+    let moves: Vec<String> = game.moves().iter().map(|mov| mov.uci()).collect();
+    let dump = format!("Moves: {}\n\n{}\n", moves.join(" "), game.game);
+    if let Ok(mut current) = GAME_DUMP.lock() {
+        *current = dump;
+    }
+}
+
+fn update_dump_on_move(_: On<SuccessfulMoveEvent>, game: Res<ChessGame>) {
+    update_dump(&game);
+}
+
+fn update_dump_on_replace(_: On<GameReplacedEvent>, game: Res<ChessGame>) {
+    update_dump(&game);
+}
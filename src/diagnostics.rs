@@ -0,0 +1,70 @@
+use bevy::{
+    diagnostic::{
+        DiagnosticPath, DiagnosticsStore, EntityCountDiagnosticsPlugin, FrameTimeDiagnosticsPlugin,
+    },
+    prelude::*,
+};
+
+/// Frame rate, frame time, entity count and scenes still loading, in the top left corner. Toggled
+/// with F3.
+#[derive(Component)]
+pub struct DiagnosticsOverlay {}
+
+pub fn toggle_diagnostics_overlay(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut commands: Commands,
+    overlays: Query<Entity, With<DiagnosticsOverlay>>,
+) {
+    if !keys.just_pressed(KeyCode::F3) {
+        return;
+    }
+    if overlays.is_empty() {
+        commands.spawn((
+            DiagnosticsOverlay {},
+            Text::default(),
+            TextFont {
+                font_size: 16.,
+                ..default()
+            },
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(12.),
+                left: Val::Px(12.),
+                padding: UiRect::all(Val::Px(6.)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0., 0., 0., 0.6)),
+        ));
+    }
+    for entity in &overlays {
+        commands.entity(entity).despawn();
+    }
+}
+
+pub fn update_diagnostics_overlay(
+    diagnostics: Res<DiagnosticsStore>,
+    asset_server: Res<AssetServer>,
+    scenes: Query<&SceneRoot>,
+    mut overlays: Query<&mut Text, With<DiagnosticsOverlay>>,
+) {
+    let Ok(mut text) = overlays.single_mut() else {
+        return;
+    };
+    let smoothed = |path: &DiagnosticPath| {
+        diagnostics
+            .get(path)
+            .and_then(|diagnostic| diagnostic.smoothed())
+            .unwrap_or_default()
+    };
+    let loading = scenes
+        .iter()
+        .filter(|scene| !asset_server.is_loaded_with_dependencies(&scene.0))
+        .count();
+    text.0 = format!(
+        "FPS: {:.0}\nFrame time: {:.1} ms\nEntities: {:.0}\nScenes loading: {}",
+        smoothed(&FrameTimeDiagnosticsPlugin::FPS),
+        smoothed(&FrameTimeDiagnosticsPlugin::FRAME_TIME),
+        smoothed(&EntityCountDiagnosticsPlugin::ENTITY_COUNT),
+        loading
+    );
+}
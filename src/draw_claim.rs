@@ -0,0 +1,165 @@
+use bevy::prelude::*;
+
+use crate::{
+    ChessGame, GameReplacedEvent, NewGameEvent, gamelogic::game::Game, input::BlocksBoardInput,
+    pause::menu_button,
+};
+
+/// Button to claim a draw by threefold repetition or the fifty-move rule. Only exists while the
+/// player to move can claim one.
+#[derive(Component)]
+pub struct ClaimDrawButton {}
+
+/// Shows the claim draw button when a draw becomes claimable and removes it when it no longer is.
+pub fn update_claim_draw_button(
+    mut commands: Commands,
+    game: Res<ChessGame>,
+    buttons: Query<Entity, With<ClaimDrawButton>>,
+    results: Query<(), With<DrawResultPanel>>,
+) {
+    if !game.is_changed() {
+        return;
+    }
+    // a claimed draw can not be claimed again
+    let claimable = game.game.can_claim_draw() && results.is_empty();
+    if claimable && buttons.is_empty() {
+        commands.spawn((
+            Button,
+            ClaimDrawButton {},
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(12.),
+                right: Val::Px(12.),
+                padding: UiRect::all(Val::Px(10.)),
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.2, 0.2, 0.2)),
+            children![(
+                Text::new("Claim draw"),
+                TextFont {
+                    font_size: 24.,
+                    ..default()
+                },
+            )],
+        ));
+    } else if !claimable {
+        for entity in &buttons {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Result of the game after a claimed draw, shown over the board until a new game is started.
+#[derive(Component)]
+pub struct DrawResultPanel {}
+
+#[derive(Component)]
+pub struct NewGameButton {}
+
+/// Why a draw can be claimed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrawReason {
+    ThreefoldRepetition,
+    FiftyMoveRule,
+}
+
+impl DrawReason {
+    /// Why the player to move can claim a draw in `game`, None if they cannot.
+    pub fn of(game: &Game) -> Option<Self> {
+        if game.repetitions() >= 3 {
+            Some(Self::ThreefoldRepetition)
+        } else if game.can_claim_draw() {
+            Some(Self::FiftyMoveRule)
+        } else {
+            None
+        }
+    }
+
+    fn result(self) -> &'static str {
+        match self {
+            Self::ThreefoldRepetition => "Draw by threefold repetition",
+            Self::FiftyMoveRule => "Draw by the fifty-move rule",
+        }
+    }
+}
+
+/// The player to move claimed a draw, which ends the game.
+#[derive(Event)]
+pub struct DrawClaimedEvent {
+    pub reason: DrawReason,
+}
+
+/// Ends the game in a draw when the claim draw button is pressed and shows the result.
+pub fn claim_draw(
+    interactions: Query<&Interaction, (Changed<Interaction>, With<ClaimDrawButton>)>,
+    buttons: Query<Entity, With<ClaimDrawButton>>,
+    game: Res<ChessGame>,
+    mut commands: Commands,
+) {
+    if !interactions
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        return;
+    }
+    let Some(reason) = DrawReason::of(&game.game) else {
+        return;
+    };
+    for entity in &buttons {
+        commands.entity(entity).despawn();
+    }
+    info!(
+        "{}, claimed by {:?}",
+        reason.result(),
+        game.game.active_color()
+    );
+    commands.trigger(DrawClaimedEvent { reason });
+    commands.spawn((
+        DrawResultPanel {},
+        BlocksBoardInput {},
+        Node {
+            width: Val::Percent(100.),
+            height: Val::Percent(100.),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(12.),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0., 0., 0., 0.6)),
+        children![
+            (
+                Text::new(reason.result()),
+                TextFont {
+                    font_size: 48.,
+                    ..default()
+                },
+            ),
+            menu_button("New game", NewGameButton {}),
+        ],
+    ));
+}
+
+/// Starts a new game when the button on the draw result is pressed.
+pub fn start_game_after_draw(
+    buttons: Query<&Interaction, (Changed<Interaction>, With<NewGameButton>)>,
+    mut commands: Commands,
+) {
+    if buttons
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        commands.trigger(NewGameEvent {});
+    }
+}
+
+/// Removes the draw result once the game is replaced, by a new game or by taking a move back.
+pub fn remove_draw_result(
+    _: On<GameReplacedEvent>,
+    mut commands: Commands,
+    panels: Query<Entity, With<DrawResultPanel>>,
+) {
+    for entity in &panels {
+        commands.entity(entity).despawn();
+    }
+}
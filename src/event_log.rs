@@ -0,0 +1,219 @@
+//! Log of the played games as JSON Lines, started with `--event-log <directory>`.
+//!
+//! Every game is written to its own file `game-<time>.jsonl`, one event per line:
+//! - `{"event": "start"}` when the game starts
+//! - `{"event": "move", "color": "white", "move": "e2e4", "balance": 0}` for every move, with the
+//!   material balance after it (positive if white is ahead)
+//! - `{"event": "end", "winner": "white", "reason": "checkmate"}` when the game ends. For draws,
+//!   the winner is null and the reason `stalemate`, `threefold repetition` or `fifty-move rule`
+//! - `{"event": "replace", "moves": ["e2e4"]}` when the game was replaced other than by a move,
+//!   e.g. by taking a move back, with the moves leading to the position now on the board
+//!
+//! Every event also has a `time` field, in milliseconds since the Unix epoch.
+
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use chess::{
+    ChessGame, GameReplacedEvent, NewGameEvent, SuccessfulMoveEvent,
+    draw_claim::{DrawClaimedEvent, DrawReason},
+    gamelogic::pieces::Color,
+};
+use serde_json::{Value, json};
+
+/// Writes the events of every game into `directory`. Has to be added after the `ChessPlugin`.
+pub struct EventLogPlugin {
+    pub directory: PathBuf,
+}
+
+impl Plugin for EventLogPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(EventLog {
+            directory: self.directory.clone(),
+            file: None,
+            moves: Vec::new(),
+        })
+        .add_systems(Startup, |mut log: ResMut<EventLog>| log.start_game())
+        .add_observer(log_new_game)
+        .add_observer(log_move)
+        .add_observer(log_replaced_game)
+        .add_observer(log_claimed_draw);
+    }
+}
+
+#[derive(Resource)]
+pub struct EventLog {
+    directory: PathBuf,
+    /// File of the current game, None if it could not be written
+    file: Option<File>,
+    /// Moves of the current game as logged so far
+    moves: Vec<String>,
+}
+
+impl EventLog {
+    fn start_game(&mut self) {
+        let path = self.directory.join(format!("game-{}.jsonl", now()));
+        self.file = match fs::create_dir_all(&self.directory).and_then(|_| File::create(&path)) {
+            Ok(file) => {
+                info!("Writing game events to {}", path.display());
+                Some(file)
+            }
+            Err(err) => {
+                warn!("Could not create event log {}: {}", path.display(), err);
+                None
+            }
+        };
+        self.moves.clear();
+        self.write(json!({ "event": "start" }));
+    }
+
+    fn end(&mut self, winner: Option<&str>, reason: &str) {
+        self.write(json!({ "event": "end", "winner": winner, "reason": reason }));
+    }
+
+    fn write(&mut self, mut event: Value) {
+        let Some(file) = &mut self.file else {
+            return;
+        };
+        event["time"] = json!(now());
+        if let Err(err) = writeln!(file, "{}", event) {
+            warn!("Could not write event log, stopping it: {}", err);
+            self.file = None;
+        }
+    }
+}
+
+/// Milliseconds since the Unix epoch.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_millis() as u64)
+}
+
+fn log_new_game(_: On<NewGameEvent>, mut log: ResMut<EventLog>) {
+    log.start_game();
+}
+
+fn log_move(_: On<SuccessfulMoveEvent>, game: Res<ChessGame>, mut log: ResMut<EventLog>) {
+    let Some(mov) = game.game.last_move else {
+        return;
+    };
+    let color = game.game.active_color().other();
+    log.moves.push(mov.uci());
+    log.write(json!({
+        "event": "move",
+        "color": color_name(color),
+        "move": mov.uci(),
+        "balance": game.game.material_imbalance().balance,
+    }));
+    if game.game.is_checkmate() {
+        log.end(Some(color_name(color)), "checkmate");
+    } else if game.game.is_stalemate() {
+        log.end(None, "stalemate");
+    }
+}
+
+/// Logs the moves of the replaced game, unless they are the ones logged already, like for a new
+/// game.
+fn log_replaced_game(_: On<GameReplacedEvent>, game: Res<ChessGame>, mut log: ResMut<EventLog>) {
+    let moves: Vec<String> = game.moves().iter().map(|mov| mov.uci()).collect();
+    if moves == log.moves {
+        return;
+    }
+    log.write(json!({ "event": "replace", "moves": moves }));
+    log.moves = moves;
+}
+
+fn log_claimed_draw(event: On<DrawClaimedEvent>, mut log: ResMut<EventLog>) {
+    let reason = match event.reason {
+        DrawReason::ThreefoldRepetition => "threefold repetition",
+        DrawReason::FiftyMoveRule => "fifty-move rule",
+    };
+    log.end(None, reason);
+}
+
+fn color_name(color: Color) -> &'static str {
+    match color {
+        Color::White => "white",
+        Color::Black => "black",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chess::{GamePlugin, TryMoveEvent, gamelogic::testing::square_at};
+
+    /// Headless app logging into a fresh directory named after `test`.
+    fn app(test: &str) -> (App, PathBuf) {
+        let directory = std::env::temp_dir().join(format!("chess-event-log-{}", test));
+        let _ = fs::remove_dir_all(&directory);
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, GamePlugin))
+            .add_plugins(EventLogPlugin {
+                directory: directory.clone(),
+            });
+        app.update();
+        (app, directory)
+    }
+
+    fn play(app: &mut App, moves: &[&str]) {
+        for name in moves {
+            app.world_mut().trigger(TryMoveEvent {
+                origin: square_at(&name[0..2]),
+                destination: square_at(&name[2..4]),
+                promotion: None,
+            });
+            app.update();
+        }
+    }
+
+    /// The logged events without their time.
+    fn events(directory: &PathBuf) -> Vec<Value> {
+        let file = fs::read_dir(directory).unwrap().next().unwrap().unwrap();
+        fs::read_to_string(file.path())
+            .unwrap()
+            .lines()
+            .map(|line| {
+                let mut event: Value = serde_json::from_str(line).unwrap();
+                event.as_object_mut().unwrap().remove("time");
+                event
+            })
+            .collect()
+    }
+
+    #[test]
+    fn checkmate_and_taken_back_moves_are_logged() {
+        let (mut app, directory) = app("checkmate");
+        play(&mut app, &["f2f3", "e7e5"]);
+        app.world_mut().resource_mut::<ChessGame>().take_back();
+        app.world_mut().trigger(GameReplacedEvent {});
+        play(&mut app, &["e7e6", "g2g4", "d8h4"]);
+
+        let events = events(&directory);
+        assert_eq!(events[0], json!({ "event": "start" }));
+        assert_eq!(events[3], json!({ "event": "replace", "moves": ["f2f3"] }));
+        assert_eq!(events[6]["move"], "d8h4");
+        assert_eq!(
+            events[7],
+            json!({ "event": "end", "winner": "black", "reason": "checkmate" })
+        );
+        assert_eq!(events.len(), 8);
+    }
+
+    #[test]
+    fn claimed_draw_is_logged() {
+        let (mut app, directory) = app("claimed-draw");
+        app.world_mut().trigger(DrawClaimedEvent {
+            reason: DrawReason::FiftyMoveRule,
+        });
+
+        assert_eq!(
+            events(&directory)[1],
+            json!({ "event": "end", "winner": null, "reason": "fifty-move rule" })
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use std::fmt;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Direction {
     North,
@@ -24,8 +26,8 @@ impl Direction {
         }
     }
 
-    pub fn all_non_diagonal() -> Vec<Self> {
-        vec![
+    pub const fn all_non_diagonal() -> &'static [Self] {
+        &[
             Direction::North,
             Direction::East,
             Direction::South,
@@ -33,8 +35,8 @@ impl Direction {
         ]
     }
 
-    pub fn all_diagonal() -> Vec<Self> {
-        vec![
+    pub const fn all_diagonal() -> &'static [Self] {
+        &[
             Direction::NorthEast,
             Direction::SouthEast,
             Direction::SouthWest,
@@ -42,10 +44,17 @@ impl Direction {
         ]
     }
 
-    pub fn all() -> Vec<Self> {
-        let mut dirs = Self::all_non_diagonal();
-        dirs.append(&mut Self::all_diagonal());
-        dirs
+    pub const fn all() -> &'static [Self] {
+        &[
+            Direction::North,
+            Direction::East,
+            Direction::South,
+            Direction::West,
+            Direction::NorthEast,
+            Direction::SouthEast,
+            Direction::SouthWest,
+            Direction::NorthWest,
+        ]
     }
 
     pub fn is_same_axis(&self, other: &Direction) -> bool {
@@ -75,16 +84,17 @@ impl Position {
     }
 
     pub fn from_str(text: &str) -> Self {
-        if text.len() != 2 {
-            panic!();
-        }
+        Self::from_str_checked(text).expect(&format!("'{}' is not a valid coordinate", text))
+    }
+
+    pub fn from_str_checked(text: &str) -> Option<Self> {
         let chars = text.chars().collect::<Vec<_>>();
-        if chars.len() != 2 {
-            panic!();
+        if chars.len() != 2 || !chars.iter().all(char::is_ascii) {
+            return None;
         }
         let x = (chars[0] as u8).wrapping_sub('A' as u8);
         let y = (chars[1] as u8).wrapping_sub('1' as u8);
-        Self::new_checked(x, y).expect(&format!("'{}' is not a valid coordinate", text))
+        Self::new_checked(x, y)
     }
 
     pub fn moved(&self, dir: Direction, amount: i8) -> Option<Self> {
@@ -94,3 +104,9 @@ impl Position {
         Self::new_checked(x, y)
     }
 }
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", (b'A' + self.x) as char, self.y + 1)
+    }
+}
@@ -1,5 +1,7 @@
+use std::cmp::Reverse;
 use std::collections::HashMap;
-use std::ops::ControlFlow;
+use std::fmt;
+use std::hash::{Hash, Hasher};
 
 use super::coordinates::Direction;
 use super::coordinates::Position;
@@ -15,7 +17,13 @@ use super::pieces::PieceType::*;
 #[derive(Debug, Clone)]
 pub struct Game {
     pieces: HashMap<Position, Piece>,
+    active_color: Color,
     pub last_move: Option<Move>,
+    castling_rights: CastlingRights,
+    /// Half moves since the last capture or pawn move
+    halfmove_clock: u32,
+    /// Positions since the last capture or pawn move, including the current one
+    positions: Vec<RepetitionKey>,
 }
 
 impl Game {
@@ -56,26 +64,108 @@ impl Game {
         pieces.insert(Position::from_str("F8"), Piece::new(Bishop, Black));
         pieces.insert(Position::from_str("G8"), Piece::new(Knight, Black));
         pieces.insert(Position::from_str("H8"), Piece::new(Rook, Black));
-        Self {
-            pieces: pieces,
+        Self::from_pieces(pieces, White)
+    }
+
+    /// Creates a game with the given pieces on the board, where `active_color` moves next. A side
+    /// may castle with each king and rook that stand unmoved on their initial squares.
+    pub fn from_pieces(
+        pieces: impl IntoIterator<Item = (Position, Piece)>,
+        active_color: Color,
+    ) -> Self {
+        let pieces: HashMap<Position, Piece> = pieces.into_iter().collect();
+        let castling_rights = CastlingRights::of_unmoved(&pieces);
+        Self::from_position(pieces, active_color, castling_rights)
+    }
+
+    /// Creates a game with the given pieces on the board, where `active_color` moves next and
+    /// may castle as given by `castling_rights`, whether the pieces have moved or not.
+    pub fn from_position(
+        pieces: impl IntoIterator<Item = (Position, Piece)>,
+        active_color: Color,
+        castling_rights: CastlingRights,
+    ) -> Self {
+        let mut game = Self {
+            pieces: pieces.into_iter().collect(),
+            active_color,
             last_move: None,
-        }
+            castling_rights,
+            halfmove_clock: 0,
+            positions: Vec::new(),
+        };
+        game.positions.push(game.repetition_key());
+        game
+    }
+
+    /// Replays `moves` from the initial position. Fails at the first move that cannot be performed.
+    pub fn from_moves(moves: &[MoveRequest]) -> Result<Self, ReplayError> {
+        moves
+            .iter()
+            .enumerate()
+            .try_fold(Self::new(), |game, (index, &request)| {
+                let error = |kind| ReplayError {
+                    index,
+                    request,
+                    kind,
+                };
+                match game.piece_at(request.origin) {
+                    None => Err(error(ReplayErrorKind::EmptyOrigin)),
+                    Some(piece) if piece.color != game.active_color() => {
+                        Err(error(ReplayErrorKind::WrongColor))
+                    }
+                    Some(_) => game
+                        .perform_move_request(request)
+                        .ok_or_else(|| error(ReplayErrorKind::Illegal)),
+                }
+            })
     }
 
     pub fn piece_at(&self, pos: Position) -> Option<Piece> {
         self.pieces.get(&pos).map(|a| *a)
     }
 
+    /// All pieces on the board with their positions, in no particular order.
+    pub fn pieces(&self) -> impl Iterator<Item = (Position, Piece)> {
+        self.pieces.iter().map(|(pos, piece)| (*pos, *piece))
+    }
+
+    /// The pieces of `color` with their positions, in no particular order.
+    pub fn pieces_of(&self, color: Color) -> impl Iterator<Item = (Position, Piece)> {
+        self.pieces().filter(move |(_, piece)| piece.color == color)
+    }
+
+    /// Positions of the pieces of the given type and color.
+    pub fn find(&self, piece_type: PieceType, color: Color) -> impl Iterator<Item = Position> {
+        self.pieces_of(color)
+            .filter(move |(_, piece)| piece.piece_type == piece_type)
+            .map(|(pos, _)| pos)
+    }
+
     pub fn active_color(&self) -> Color {
-        self.last_move
-            .map(|mov| match mov {
-                Move::NormalMove(normal_move) => normal_move.destination,
-                Move::EnPassante(en_passante) => en_passante.destination,
-                Move::Castling(castling) => castling.king_destination,
-                Move::Promotion(promotion) => promotion.destination,
-            })
-            .map(|destination| self.piece_at(destination).unwrap().color.other())
-            .unwrap_or(Color::White)
+        self.active_color
+    }
+
+    pub fn castling_rights(&self) -> CastlingRights {
+        self.castling_rights
+    }
+
+    /// Square a pawn passed over by advancing two squares in the last move. As in FEN, it is set
+    /// whether or not an opponent pawn can capture en passant.
+    pub fn en_passant_target(&self) -> Option<Position> {
+        match self.last_move {
+            Some(Move::NormalMove(normal_move))
+                if self
+                    .piece_at(normal_move.destination)
+                    .is_some_and(|piece| piece.piece_type == Pawn)
+                    && normal_move.origin.y.abs_diff(normal_move.destination.y) == 2 =>
+            {
+                Some(Position::new(
+                    normal_move.origin.x,
+                    (normal_move.origin.y + normal_move.destination.y) / 2,
+                ))
+            }
+            _ => None,
+        }
     }
 
     pub fn perform_move_request(&self, move_req: MoveRequest) -> Option<Self> {
@@ -100,10 +190,9 @@ impl Game {
                 moving_piece.has_moved = true;
                 pieces.insert(normal_move.destination, moving_piece);
 
-                Some(Game {
-                    pieces,
-                    last_move: Some(mov),
-                })
+                let irreversible =
+                    normal_move.throwing.is_some() || moving_piece.piece_type == Pawn;
+                Some(self.after_move(pieces, mov, irreversible))
             }
             Move::EnPassante(en_passante) => {
                 let mut pieces = self.pieces.clone();
@@ -111,10 +200,7 @@ impl Game {
                 pieces.insert(en_passante.destination, moving_piece);
                 pieces.remove(&en_passante.throwing.0);
 
-                Some(Game {
-                    pieces,
-                    last_move: Some(mov),
-                })
+                Some(self.after_move(pieces, mov, true))
             }
             Move::Castling(castling) => {
                 let mut pieces = self.pieces.clone();
@@ -125,120 +211,608 @@ impl Game {
                 rook.has_moved = true;
                 pieces.insert(castling.rook_destination, rook);
 
-                Some(Game {
-                    pieces,
-                    last_move: Some(mov),
-                })
+                Some(self.after_move(pieces, mov, false))
             }
-            Move::Promotion(_promotion) => {
-                todo!();
+            Move::Promotion(promotion) => {
+                let mut pieces = self.pieces.clone();
+                pieces.remove(&promotion.origin);
+                let mut new_piece = promotion.new_piece;
+                new_piece.has_moved = true;
+                pieces.insert(promotion.destination, new_piece);
+
+                Some(self.after_move(pieces, mov, true))
             }
         }
     }
 
-    pub fn winner(&self) -> Option<Color> {
+    /// The game after `mov`, which led to `pieces`. An irreversible move (capture or pawn move)
+    /// resets the fifty-move count and the repetition history.
+    fn after_move(&self, pieces: HashMap<Position, Piece>, mov: Move, irreversible: bool) -> Self {
+        let mut game = Game {
+            pieces,
+            active_color: self.active_color.other(),
+            last_move: Some(mov),
+            // moving a king or rook, or capturing a rook, loses the castling rights involving them
+            castling_rights: self
+                .castling_rights
+                .without_square(mov.origin())
+                .without_square(mov.destination()),
+            halfmove_clock: if irreversible {
+                0
+            } else {
+                self.halfmove_clock + 1
+            },
+            positions: if irreversible {
+                Vec::new()
+            } else {
+                self.positions.clone()
+            },
+        };
+        game.positions.push(game.repetition_key());
+        game
+    }
+
+    fn repetition_key(&self) -> RepetitionKey {
+        let mut squares = [None; 64];
+        for (pos, piece) in self.pieces() {
+            squares[pos.y as usize * 8 + pos.x as usize] = Some((piece.piece_type, piece.color));
+        }
+        // only counts if a pawn of the player to move stands next to the passed pawn
+        let capturing_from = match self.active_color {
+            White => [Direction::SouthWest, Direction::SouthEast],
+            Black => [Direction::NorthWest, Direction::NorthEast],
+        };
+        let en_passant = self.en_passant_target().filter(|target| {
+            capturing_from.iter().any(|dir| {
+                target
+                    .moved(*dir, 1)
+                    .and_then(|pos| self.piece_at(pos))
+                    .is_some_and(|piece| {
+                        piece.piece_type == Pawn && piece.color == self.active_color
+                    })
+            })
+        });
+        RepetitionKey {
+            squares,
+            active_color: self.active_color,
+            castling: self.castling_rights,
+            en_passant,
+        }
+    }
+
+    /// Half moves since the last capture or pawn move.
+    pub fn halfmove_clock(&self) -> u32 {
+        self.halfmove_clock
+    }
+
+    /// How often the current position has occurred, including now.
+    pub fn repetitions(&self) -> usize {
+        let current = self.positions.last();
+        self.positions
+            .iter()
+            .filter(|key| Some(*key) == current)
+            .count()
+    }
+
+    /// Whether the player to move can claim a draw (FIDE Laws 9.2 and 9.3): the current position
+    /// occurred for the third time, or no capture or pawn move happened in the last fifty moves of
+    /// each player. Claims announcing a move that would lead to such a position are not covered.
+    pub fn can_claim_draw(&self) -> bool {
+        self.repetitions() >= 3 || self.halfmove_clock >= 100
+    }
+
+    /// All valid moves of the active color.
+    pub fn legal_moves(&self) -> Vec<Move> {
         let active = self.active_color();
-        if self
-            .pieces
+        self.pieces_of(active)
+            .flat_map(|(pos, _)| moves::valid_moves(pos, self))
+            .collect()
+    }
+
+    /// Legal moves of the active color that capture a piece, including promotions that capture.
+    pub fn captures(&self) -> Vec<Move> {
+        let mut moves = self.legal_moves();
+        moves.retain(Move::is_capture);
+        moves
+    }
+
+    /// Legal moves of the active color that do not capture.
+    pub fn quiet_moves(&self) -> Vec<Move> {
+        let mut moves = self.legal_moves();
+        moves.retain(|mov| !mov.is_capture());
+        moves
+    }
+
+    /// Legal moves of the active color getting its king out of check. Empty if the king is not in
+    /// check.
+    pub fn evasions(&self) -> Vec<Move> {
+        match self.checkers(self.active_color).len() {
+            0 => Vec::new(),
+            1 => self.legal_moves(),
+            // no single move can block or capture two checking pieces
+            _ => {
+                let king = self.find(King, self.active_color).next().unwrap();
+                moves::valid_moves(king, self)
+            }
+        }
+    }
+
+    /// Counts the leaf nodes of the move tree of the given depth.
+    pub fn perft(&self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        let moves = self.legal_moves();
+        if depth == 1 {
+            return moves.len() as u64;
+        }
+        moves
+            .into_iter()
+            .filter_map(|mov| self.perform_move(mov))
+            .map(|game| game.perft(depth - 1))
+            .sum()
+    }
+
+    /// The player who checkmated the player to move, None if they are not checkmated.
+    pub fn winner(&self) -> Option<Color> {
+        if self.is_checkmate() {
+            Some(self.active_color().other())
+        } else {
+            None
+        }
+    }
+
+    /// Whether `color` could checkmate the opponent by any series of legal moves, as far as the
+    /// material on the board is concerned.
+    pub fn has_mating_material(&self, color: Color) -> bool {
+        let without_king = |color: Color| -> Vec<(Position, Piece)> {
+            self.pieces_of(color)
+                .filter(|(_, piece)| piece.piece_type != King)
+                .collect()
+        };
+        let own = without_king(color);
+        let opponent = without_king(color.other());
+
+        if own.is_empty() {
+            return false;
+        }
+        if own
             .iter()
-            .filter(|(_, piece)| piece.color == active)
-            .all(|(pos, _)| moves::valid_destinations(*pos, self).len() == 0)
+            .any(|(_, piece)| matches!(piece.piece_type, Pawn | Rook | Queen))
         {
-            Some(active.other())
+            return true;
+        }
+        if own.iter().any(|(_, piece)| piece.piece_type == Knight) {
+            // a lone knight needs an opponent piece blocking the escape square of the king
+            return own.len() > 1 || !opponent.is_empty();
+        }
+
+        // only bishops: if they all move on squares of one color, the opponent king can only be
+        // mated if an opponent piece blocks a square of the other color
+        let square_color = |pos: &Position| (pos.x + pos.y) % 2;
+        let bishop_square_color = square_color(&own[0].0);
+        if own
+            .iter()
+            .any(|(pos, _)| square_color(pos) != bishop_square_color)
+        {
+            return true;
+        }
+        opponent.iter().any(|(pos, piece)| {
+            piece.piece_type != Bishop || square_color(pos) != bishop_square_color
+        })
+    }
+
+    /// Result of `flagged` running out of time: the opponent wins, unless they do not have
+    /// mating material, in which case the game is drawn (None).
+    pub fn flag_fall_winner(&self, flagged: Color) -> Option<Color> {
+        let opponent = flagged.other();
+        if self.has_mating_material(opponent) {
+            Some(opponent)
         } else {
             None
         }
     }
 
+    /// Sum of the standard values of the pieces of `color`.
+    pub fn material(&self, color: Color) -> u32 {
+        self.pieces_of(color)
+            .map(|(_, piece)| piece.piece_type.value())
+            .sum()
+    }
+
+    /// Material of both sides compared piece by piece.
+    pub fn material_imbalance(&self) -> MaterialImbalance {
+        let mut white_extra: Vec<PieceType> = self
+            .pieces_of(White)
+            .map(|(_, piece)| piece.piece_type)
+            .filter(|&piece_type| piece_type != King)
+            .collect();
+        let mut black_extra = Vec::new();
+        for (_, piece) in self.pieces_of(Black) {
+            if piece.piece_type == King {
+                continue;
+            }
+            match white_extra.iter().position(|&own| own == piece.piece_type) {
+                Some(index) => {
+                    white_extra.swap_remove(index);
+                }
+                None => black_extra.push(piece.piece_type),
+            }
+        }
+        // most valuable first, pieces of the same type next to each other
+        let order = |piece_type: &PieceType| (Reverse(piece_type.value()), *piece_type as u8);
+        white_extra.sort_by_key(order);
+        black_extra.sort_by_key(order);
+        MaterialImbalance {
+            balance: self.material(White) as i32 - self.material(Black) as i32,
+            white_extra,
+            black_extra,
+        }
+    }
+
+    /// Whether the player to move is checkmated.
+    pub fn is_checkmate(&self) -> bool {
+        self.is_king_in_check(self.active_color) && self.legal_moves().is_empty()
+    }
+
+    /// Whether the player to move has no legal move without being in check, a draw.
+    pub fn is_stalemate(&self) -> bool {
+        !self.is_king_in_check(self.active_color) && self.legal_moves().is_empty()
+    }
+
+    /// All moves of the player to move that force checkmate in at most `n` moves, whatever the
+    /// opponent replies.
+    pub fn mating_moves(&self, n: u32) -> Vec<Move> {
+        if n == 0 {
+            return Vec::new();
+        }
+        self.legal_moves()
+            .into_iter()
+            .filter(|mov| {
+                self.perform_move(*mov)
+                    .is_some_and(|game| game.is_mated_within(n - 1))
+            })
+            .collect()
+    }
+
+    /// Whether the player to move is checkmated now or within `n` moves of the opponent.
+    fn is_mated_within(&self, n: u32) -> bool {
+        let replies = self.legal_moves();
+        if replies.is_empty() {
+            return self.is_king_in_check(self.active_color);
+        }
+        n > 0
+            && replies.into_iter().all(|reply| {
+                self.perform_move(reply).is_some_and(|game| {
+                    game.legal_moves().into_iter().any(|mov| {
+                        game.perform_move(mov)
+                            .is_some_and(|game| game.is_mated_within(n - 1))
+                    })
+                })
+            })
+    }
+
+    /// Whether the player to move could capture a piece standing on `pos`.
+    pub fn is_capturable(&self, pos: Position) -> bool {
+        self.legal_moves().iter().any(|mov| match mov {
+            Move::NormalMove(normal_move) => normal_move.destination == pos,
+            Move::EnPassante(en_passante) => en_passante.throwing.0 == pos,
+            Move::Promotion(promotion) => promotion.destination == pos,
+            Move::Castling(_) => false,
+        })
+    }
+
     pub fn is_king_in_check(&self, color: Color) -> bool {
-        let king_pos = self
-            .pieces
+        self.king_attackers(color).next().is_some()
+    }
+
+    /// Squares of the opponent pieces giving check to the king of `color`.
+    pub fn checkers(&self, color: Color) -> Vec<Position> {
+        self.king_attackers(color).collect()
+    }
+
+    /// Pieces of `color` that are pinned to their king, see `pins`.
+    pub fn pinned_pieces(&self, color: Color) -> Vec<Position> {
+        self.pins(color).into_iter().map(|pin| pin.pinned).collect()
+    }
+
+    /// Pieces of `color` standing between their king and an opponent bishop, rook or queen
+    /// attacking along that line. They cannot leave the line without exposing the king.
+    pub fn pins(&self, color: Color) -> Vec<Pin> {
+        let king_pos = self.find(King, color).next().unwrap();
+        Direction::all()
             .iter()
-            .filter(|(_, piece)| piece.piece_type == PieceType::King && piece.color == color)
-            .map(|(pos, _)| pos)
-            .next()
-            .unwrap();
-        let enemy_color = color.other();
+            .filter_map(|dir| {
+                let mut occupied = (1..8)
+                    .filter_map(|i| king_pos.moved(*dir, i))
+                    .filter_map(|pos| self.piece_at(pos).map(|piece| (pos, piece)));
+                let (pinned, own) = occupied.next()?;
+                let (pinner, enemy) = occupied.next()?;
+                let slides_along = if Direction::all_diagonal().contains(dir) {
+                    matches!(enemy.piece_type, Bishop | Queen)
+                } else {
+                    matches!(enemy.piece_type, Rook | Queen)
+                };
+                (own.color == color && enemy.color != color && slides_along)
+                    .then_some(Pin { pinned, pinner })
+            })
+            .collect()
+    }
 
-        let diag_attack = Direction::all_diagonal().iter().any(|dir| {
-            (1..8)
-                .filter_map(|i| king_pos.moved(*dir, i))
-                .try_fold(false, |_, e| match self.piece_at(e) {
-                    Some(piece)
-                        if piece.piece_type == PieceType::Bishop && piece.color == enemy_color =>
-                    {
-                        ControlFlow::Break(true)
-                    }
-                    Some(piece)
-                        if piece.piece_type == PieceType::Queen && piece.color == enemy_color =>
-                    {
-                        ControlFlow::Break(true)
-                    }
-                    Some(_) => ControlFlow::Break(false),
-                    None => ControlFlow::Continue(false),
-                })
-                .break_value()
-                .unwrap_or(false)
-        });
+    fn king_attackers(&self, color: Color) -> impl Iterator<Item = Position> {
+        let king_pos = self.find(King, color).next().unwrap();
+        let enemy_color = color.other();
+        let is_enemy = move |pos: Position, piece_types: &[PieceType]| {
+            self.piece_at(pos).is_some_and(|piece| {
+                piece.color == enemy_color && piece_types.contains(&piece.piece_type)
+            })
+        };
 
-        let straight_attack = Direction::all_non_diagonal().iter().any(|dir| {
-            (1..8)
-                .filter_map(|i| king_pos.moved(*dir, i))
-                .try_fold(false, |_, e| match self.piece_at(e) {
-                    Some(piece)
-                        if piece.piece_type == PieceType::Rook && piece.color == enemy_color =>
-                    {
-                        ControlFlow::Break(true)
-                    }
-                    Some(piece)
-                        if piece.piece_type == PieceType::Queen && piece.color == enemy_color =>
-                    {
-                        ControlFlow::Break(true)
-                    }
-                    Some(_) => ControlFlow::Break(false),
-                    None => ControlFlow::Continue(false),
-                })
-                .break_value()
-                .unwrap_or(false)
-        });
+        // first piece in each direction, if it is one of `piece_types`
+        let slider_attack = move |dirs: &'static [Direction], piece_types: &'static [PieceType]| {
+            dirs.iter().filter_map(move |dir| {
+                (1..8)
+                    .filter_map(|i| king_pos.moved(*dir, i))
+                    .find(|pos| self.piece_at(*pos).is_some())
+                    .filter(|pos| is_enemy(*pos, piece_types))
+            })
+        };
+        let diag_attack = slider_attack(Direction::all_diagonal(), &[Bishop, Queen]);
+        let straight_attack = slider_attack(Direction::all_non_diagonal(), &[Rook, Queen]);
 
-        let knight_attack = Direction::all_non_diagonal().iter().any(|first_dir| {
-            Direction::all_non_diagonal()
-                .iter()
-                .filter(|second_dir| !first_dir.is_same_axis(*second_dir))
-                .any(|second_dir| {
-                    king_pos
-                        .moved(*first_dir, 2)
-                        .and_then(|pos| pos.moved(*second_dir, 1))
-                        .and_then(|pos| self.piece_at(pos))
-                        .map(|piece| {
-                            piece.piece_type == PieceType::Knight && piece.color == enemy_color
-                        })
-                        .unwrap_or(false)
-                })
-        });
+        let knight_attack = Direction::all_non_diagonal()
+            .iter()
+            .flat_map(|first_dir| {
+                Direction::all_non_diagonal()
+                    .iter()
+                    .filter(|second_dir| !first_dir.is_same_axis(*second_dir))
+                    .map(move |second_dir| (first_dir, second_dir))
+            })
+            .filter_map(move |(first_dir, second_dir)| {
+                king_pos
+                    .moved(*first_dir, 2)
+                    .and_then(|pos| pos.moved(*second_dir, 1))
+            })
+            .filter(move |pos| is_enemy(*pos, &[Knight]));
 
         let pawn_dir = match color {
             Color::White => Direction::North,
             Color::Black => Direction::South,
         };
-        let pawn_attack = vec![Direction::West, Direction::East].iter().any(|dir| {
-            king_pos
-                .moved(pawn_dir, 1)
-                .and_then(|pos| pos.moved(*dir, 1))
-                .and_then(|pos| self.piece_at(pos))
-                .map(|piece| piece.piece_type == PieceType::Pawn && piece.color == enemy_color)
-                .unwrap_or(false)
-        });
+        let pawn_attack = [Direction::West, Direction::East]
+            .into_iter()
+            .filter_map(move |dir| king_pos.moved(pawn_dir, 1)?.moved(dir, 1))
+            .filter(move |pos| is_enemy(*pos, &[Pawn]));
 
-        let king_attack = Direction::all().iter().any(|dir| {
-            king_pos
-                .moved(*dir, 1)
-                .and_then(|pos| self.piece_at(pos))
-                .map(|piece| piece.piece_type == PieceType::King && piece.color == enemy_color)
-                .unwrap_or(false)
-        });
+        let king_attack = Direction::all()
+            .iter()
+            .filter_map(move |dir| king_pos.moved(*dir, 1))
+            .filter(move |pos| is_enemy(*pos, &[King]));
+
+        diag_attack
+            .chain(straight_attack)
+            .chain(knight_attack)
+            .chain(pawn_attack)
+            .chain(king_attack)
+    }
+}
+
+/// Absolute pin found by `Game::pins`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pin {
+    pub pinned: Position,
+    /// The attacking piece behind the pinned one
+    pub pinner: Position,
+}
 
-        diag_attack || straight_attack || knight_attack || pawn_attack || king_attack
+/// Castlings each side may still perform at some point in the game, whether they are possible in
+/// the current position or not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CastlingRights {
+    pub white_kingside: bool,
+    pub white_queenside: bool,
+    pub black_kingside: bool,
+    pub black_queenside: bool,
+}
+
+impl CastlingRights {
+    pub const NONE: Self = Self {
+        white_kingside: false,
+        white_queenside: false,
+        black_kingside: false,
+        black_queenside: false,
+    };
+
+    pub const ALL: Self = Self {
+        white_kingside: true,
+        white_queenside: true,
+        black_kingside: true,
+        black_queenside: true,
+    };
+
+    pub fn kingside(&self, color: Color) -> bool {
+        match color {
+            White => self.white_kingside,
+            Black => self.black_kingside,
+        }
+    }
+
+    pub fn queenside(&self, color: Color) -> bool {
+        match color {
+            White => self.white_queenside,
+            Black => self.black_queenside,
+        }
+    }
+
+    /// Rights of the kings and rooks in `pieces` that stand unmoved on their initial squares.
+    fn of_unmoved(pieces: &HashMap<Position, Piece>) -> Self {
+        let unmoved = |square: &str, piece_type: PieceType, color: Color| {
+            pieces
+                .get(&Position::from_str(square))
+                .is_some_and(|piece| {
+                    piece.piece_type == piece_type && piece.color == color && !piece.has_moved
+                })
+        };
+        let white_king = unmoved("E1", King, White);
+        let black_king = unmoved("E8", King, Black);
+        Self {
+            white_kingside: white_king && unmoved("H1", Rook, White),
+            white_queenside: white_king && unmoved("A1", Rook, White),
+            black_kingside: black_king && unmoved("H8", Rook, Black),
+            black_queenside: black_king && unmoved("A8", Rook, Black),
+        }
+    }
+
+    /// The rights left after a king or rook leaves `pos`, or a rook is captured on it.
+    fn without_square(self, pos: Position) -> Self {
+        if pos.y != 0 && pos.y != 7 {
+            return self;
+        }
+        let square = |name: &str| pos == Position::from_str(name);
+        Self {
+            white_kingside: self.white_kingside && !square("E1") && !square("H1"),
+            white_queenside: self.white_queenside && !square("E1") && !square("A1"),
+            black_kingside: self.black_kingside && !square("E8") && !square("H8"),
+            black_queenside: self.black_queenside && !square("E8") && !square("A8"),
+        }
+    }
+}
+
+/// Prints the board from white's side with rank and file labels, followed by the side to move.
+/// Empty squares are dots.
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for y in (0..8).rev() {
+            write!(f, "{}", y + 1)?;
+            for x in 0..8 {
+                let symbol = self
+                    .piece_at(Position::new(x, y))
+                    .map_or('·', |piece| piece.symbol());
+                write!(f, " {}", symbol)?;
+            }
+            writeln!(f)?;
+        }
+        writeln!(f, "  a b c d e f g h")?;
+        write!(f, "{:?} to move", self.active_color)
+    }
+}
+
+/// Games are equal if they are in the same position by the repetition rule, whatever moves led
+/// there, see `RepetitionKey`.
+impl PartialEq for Game {
+    fn eq(&self, other: &Self) -> bool {
+        self.positions.last() == other.positions.last()
+    }
+}
+
+impl Eq for Game {}
+
+impl Hash for Game {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.positions.last().hash(state);
+    }
+}
+
+/// What makes two positions the same for the repetition rule: the placement of the pieces, the
+/// player to move, the castling rights and the possibility to capture en passant.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct RepetitionKey {
+    /// Type and color of the piece on each square, indexed by `y * 8 + x`
+    squares: [Option<(PieceType, Color)>; 64],
+    active_color: Color,
+    castling: CastlingRights,
+    /// Square behind the pawn that can be captured en passant
+    en_passant: Option<Position>,
+}
+
+/// Why a move of `Game::from_moves` could not be performed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayErrorKind {
+    /// There is no piece on the origin square
+    EmptyOrigin,
+    /// The piece on the origin square belongs to the side that is not to move
+    WrongColor,
+    /// The piece cannot move to the destination
+    Illegal,
+}
+
+/// First move of a replay that could not be performed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplayError {
+    /// Index of the move in the replayed list
+    pub index: usize,
+    pub request: MoveRequest,
+    pub kind: ReplayErrorKind,
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let reason = match self.kind {
+            ReplayErrorKind::EmptyOrigin => "no piece on the origin square",
+            ReplayErrorKind::WrongColor => "piece of the side not to move",
+            ReplayErrorKind::Illegal => "illegal move",
+        };
+        write!(
+            f,
+            "move {} ({}-{}): {}",
+            self.index + 1,
+            self.request.origin,
+            self.request.destination,
+            reason
+        )
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+/// Material difference between white and black, see `Game::material_imbalance`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MaterialImbalance {
+    /// Material of white minus material of black
+    pub balance: i32,
+    /// Pieces of white without a counterpart of the same type on the black side, most valuable
+    /// first
+    pub white_extra: Vec<PieceType>,
+    /// Pieces of black without a counterpart of the same type on the white side, most valuable
+    /// first
+    pub black_extra: Vec<PieceType>,
+}
+
+impl MaterialImbalance {
+    fn describe(pieces: &[PieceType]) -> String {
+        if pieces.is_empty() {
+            return "nothing".to_string();
+        }
+        let mut parts = Vec::new();
+        let mut rest = pieces;
+        while let Some(&piece_type) = rest.first() {
+            let count = rest
+                .iter()
+                .take_while(|&&other| other == piece_type)
+                .count();
+            let name = format!("{:?}", piece_type).to_lowercase();
+            parts.push(match count {
+                1 => name,
+                2 if piece_type == Bishop => "bishop pair".to_string(),
+                _ => format!("{} {}s", count, name),
+            });
+            rest = &rest[count..];
+        }
+        parts.join("+")
+    }
+}
+
+/// Lists the unmatched pieces of both sides, like "bishop pair vs knight+pawn".
+impl fmt::Display for MaterialImbalance {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.white_extra.is_empty() && self.black_extra.is_empty() {
+            return write!(f, "equal material");
+        }
+        write!(
+            f,
+            "{} vs {}",
+            Self::describe(&self.white_extra),
+            Self::describe(&self.black_extra)
+        )
     }
 }
@@ -2,3 +2,5 @@ pub mod coordinates;
 pub mod game;
 pub mod moves;
 pub mod pieces;
+pub mod testing;
+pub mod tree;
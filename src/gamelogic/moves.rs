@@ -1,4 +1,4 @@
-use std::ops::ControlFlow;
+use std::fmt;
 
 use crate::gamelogic::coordinates::Direction;
 
@@ -8,7 +8,7 @@ use super::{
     pieces::{Color, Piece, PieceType},
 };
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Move {
     NormalMove(NormalMove),
     EnPassante(EnPassante),
@@ -16,21 +16,68 @@ pub enum Move {
     Promotion(Promotion),
 }
 
-#[derive(Debug, Clone, Copy)]
+impl Move {
+    /// The square the moving piece comes from, which is the king's for castling.
+    pub fn origin(&self) -> Position {
+        match self {
+            Move::NormalMove(normal_move) => normal_move.origin,
+            Move::EnPassante(en_passante) => en_passante.origin,
+            Move::Castling(castling) => castling.king_origin,
+            Move::Promotion(promotion) => promotion.origin,
+        }
+    }
+
+    /// Whether the move captures a piece.
+    pub fn is_capture(&self) -> bool {
+        match self {
+            Move::NormalMove(normal_move) => normal_move.throwing.is_some(),
+            Move::EnPassante(_) => true,
+            Move::Castling(_) => false,
+            Move::Promotion(promotion) => promotion.throwing.is_some(),
+        }
+    }
+
+    /// Name of the move in UCI notation, e.g. `e2e4` or `e7e8q`.
+    pub fn uci(&self) -> String {
+        let promotion = match self {
+            Move::Promotion(promotion) => match promotion.new_piece.piece_type {
+                PieceType::Queen => "q",
+                PieceType::Rook => "r",
+                PieceType::Bishop => "b",
+                PieceType::Knight => "n",
+                PieceType::King | PieceType::Pawn => "",
+            },
+            _ => "",
+        };
+        format!("{}{}{}", self.origin(), self.destination(), promotion).to_lowercase()
+    }
+
+    /// The square the moving piece ends up on, which is the king's for castling.
+    pub fn destination(&self) -> Position {
+        match self {
+            Move::NormalMove(normal_move) => normal_move.destination,
+            Move::EnPassante(en_passante) => en_passante.destination,
+            Move::Castling(castling) => castling.king_destination,
+            Move::Promotion(promotion) => promotion.destination,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct NormalMove {
     pub origin: Position,
     pub destination: Position,
     pub throwing: Option<Piece>,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct EnPassante {
     pub origin: Position,
     pub destination: Position,
     pub throwing: (Position, Piece),
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Castling {
     pub king_origin: Position,
     pub king_destination: Position,
@@ -38,17 +85,19 @@ pub struct Castling {
     pub rook_destination: Position,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Promotion {
     pub origin: Position,
     pub destination: Position,
     pub new_piece: Piece,
+    pub throwing: Option<Piece>,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct MoveRequest {
     pub origin: Position,
     pub destination: Position,
+    /// Piece a pawn reaching the last rank turns into, a queen if None
     pub promotion: Option<Piece>,
 }
 
@@ -61,8 +110,24 @@ impl MoveRequest {
         }
     }
 
+    /// Parses a move in UCI notation, e.g. `e2e4` or `e7e8q`, of the player with `color`.
+    pub fn parse(name: &str, color: Color) -> Option<Self> {
+        let name = name.to_ascii_uppercase();
+        let origin = Position::from_str_checked(name.get(0..2)?)?;
+        let destination = Position::from_str_checked(name.get(2..4)?)?;
+        let promotion = match name.get(4..)? {
+            "" => None,
+            "Q" => Some(Piece::new(PieceType::Queen, color)),
+            "R" => Some(Piece::new(PieceType::Rook, color)),
+            "B" => Some(Piece::new(PieceType::Bishop, color)),
+            "N" => Some(Piece::new(PieceType::Knight, color)),
+            _ => return None,
+        };
+        Some(Self::new(origin, destination, promotion))
+    }
+
     pub fn to_move(&self, game: &Game) -> Option<Move> {
-        valid_destinations_with_special_cases(self.origin, game)
+        valid_moves(self.origin, game)
             .into_iter()
             .filter(|mov| match mov {
                 Move::NormalMove(normal_move) => {
@@ -76,28 +141,188 @@ impl MoveRequest {
                         && castling.king_destination == self.destination
                 }
                 Move::Promotion(promotion) => {
+                    let piece_type = self
+                        .promotion
+                        .map_or(PieceType::Queen, |piece| piece.piece_type);
                     promotion.origin == self.origin
                         && promotion.destination == self.destination
-                        && Some(promotion.new_piece) == self.promotion
+                        && promotion.new_piece.piece_type == piece_type
                 }
             })
             .next()
     }
+
+    /// The move this request stands for in `game`, or why it cannot be performed.
+    pub fn check(&self, game: &Game) -> Result<Move, MoveError> {
+        let piece = game.piece_at(self.origin).ok_or(MoveError::NoPiece)?;
+        if piece.color != game.active_color() {
+            return Err(MoveError::NotYourTurn);
+        }
+        if let Some(mov) = self.to_move(game) {
+            return Ok(mov);
+        }
+        if game
+            .piece_at(self.destination)
+            .is_some_and(|other| other.color == piece.color)
+        {
+            return Err(MoveError::OwnPiece);
+        }
+        if piece.piece_type == PieceType::King
+            && self.origin.y == self.destination.y
+            && self.origin.x.abs_diff(self.destination.x) == 2
+        {
+            return Err(castling_error(self.origin, self.destination, game));
+        }
+        if pseudo_legal_moves(self.origin, game)
+            .iter()
+            .any(|mov| mov.destination() == self.destination)
+        {
+            return Err(MoveError::KingInCheck);
+        }
+        Err(MoveError::Unreachable)
+    }
+}
+
+/// Why a move request cannot be performed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveError {
+    /// There is no piece on the origin square
+    NoPiece,
+    /// The piece belongs to the player who is not to move
+    NotYourTurn,
+    /// A piece of the same color stands on the destination
+    OwnPiece,
+    /// The piece cannot move to the destination in any position
+    Unreachable,
+    /// The move would leave the own king in check
+    KingInCheck,
+    /// Castling after the king has moved
+    KingMoved,
+    /// Castling with a rook that has moved or is gone
+    RookMoved,
+    /// Castling while a piece stands on the given square between king and rook
+    CastlingBlocked(Position),
+    /// Castling while the king is in check
+    CastlingOutOfCheck,
+    /// Castling while the king passes the given attacked square
+    CastlingThroughCheck(Position),
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MoveError::NoPiece => write!(f, "there is no piece on this square"),
+            MoveError::NotYourTurn => write!(f, "it is not your turn"),
+            MoveError::OwnPiece => write!(f, "the square is occupied by your own piece"),
+            MoveError::Unreachable => write!(f, "the piece cannot move there"),
+            MoveError::KingInCheck => write!(f, "your king would be in check"),
+            MoveError::KingMoved => write!(f, "castling not possible: the king has moved"),
+            MoveError::RookMoved => write!(f, "castling not possible: the rook has moved"),
+            MoveError::CastlingBlocked(pos) => {
+                write!(f, "castling blocked: square {pos} is occupied")
+            }
+            MoveError::CastlingOutOfCheck => write!(f, "castling not possible while in check"),
+            MoveError::CastlingThroughCheck(pos) => {
+                write!(f, "castling blocked: square {pos} is attacked")
+            }
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+/// Why the king on `origin` cannot castle towards `destination`.
+fn castling_error(origin: Position, destination: Position, game: &Game) -> MoveError {
+    let king = game.piece_at(origin).unwrap();
+    let home = match king.color {
+        Color::White => Position::from_str("E1"),
+        Color::Black => Position::from_str("E8"),
+    };
+    let rights = game.castling_rights();
+    if origin != home || !rights.kingside(king.color) && !rights.queenside(king.color) {
+        return MoveError::KingMoved;
+    }
+
+    let (dir, rook_distance, right) = if destination.x > origin.x {
+        (Direction::East, 3, rights.kingside(king.color))
+    } else {
+        (Direction::West, 4, rights.queenside(king.color))
+    };
+    let rook_pos = origin.moved(dir, rook_distance).unwrap();
+    if !right || !is_own_rook(rook_pos, king.color, game) {
+        return MoveError::RookMoved;
+    }
+    if let Some(pos) = (1..rook_distance)
+        .filter_map(|distance| origin.moved(dir, distance))
+        .find(|pos| game.piece_at(*pos).is_some())
+    {
+        return MoveError::CastlingBlocked(pos);
+    }
+    if game.is_king_in_check(king.color) {
+        return MoveError::CastlingOutOfCheck;
+    }
+    if is_passing_square_attacked(origin, dir, game) {
+        return MoveError::CastlingThroughCheck(origin.moved(dir, 1).unwrap());
+    }
+    MoveError::KingInCheck
 }
 
 pub fn valid_destinations(origin: Position, game: &Game) -> Vec<Position> {
-    valid_destinations_with_special_cases(origin, game)
+    valid_moves(origin, game)
         .into_iter()
-        .map(|mov| match mov {
-            Move::NormalMove(normal_move) => normal_move.destination,
-            Move::EnPassante(en_passante) => en_passante.destination,
-            Move::Castling(castling) => castling.king_destination,
-            Move::Promotion(promotion) => promotion.destination,
+        .map(|mov| mov.destination())
+        .collect()
+}
+
+pub fn valid_moves(origin: Position, game: &Game) -> Vec<Move> {
+    let piece = match game.piece_at(origin) {
+        Some(piece) => piece,
+        None => return Vec::new(),
+    };
+    let moves = pseudo_legal_moves(origin, game);
+
+    // Without check, a piece other than the king can only expose its king by leaving the line of
+    // a pin. En passant also removes the captured pawn and is tested by performing it.
+    if piece.piece_type != PieceType::King && !game.is_king_in_check(piece.color) {
+        let pinner = game
+            .pins(piece.color)
+            .into_iter()
+            .find(|pin| pin.pinned == origin)
+            .map(|pin| pin.pinner);
+        let king = game.find(PieceType::King, piece.color).next().unwrap();
+        return moves
+            .into_iter()
+            .filter(|mov| match (mov, pinner) {
+                (Move::EnPassante(_), _) => !game
+                    .perform_move(*mov)
+                    .unwrap()
+                    .is_king_in_check(piece.color),
+                (_, None) => true,
+                (_, Some(pinner)) => on_line(king, pinner, mov.destination()),
+            })
+            .collect();
+    }
+
+    moves
+        .into_iter()
+        .filter(|mov| {
+            !game
+                .perform_move(*mov)
+                .unwrap()
+                .is_king_in_check(piece.color)
         })
         .collect()
 }
 
-fn valid_destinations_with_special_cases(origin: Position, game: &Game) -> Vec<Move> {
+/// Whether `pos` lies on the line through `a` and `b`.
+fn on_line(a: Position, b: Position, pos: Position) -> bool {
+    let (dx, dy) = (b.x as i8 - a.x as i8, b.y as i8 - a.y as i8);
+    let (px, py) = (pos.x as i8 - a.x as i8, pos.y as i8 - a.y as i8);
+    dx * py == dy * px
+}
+
+/// Moves of the piece on `origin`, including those that leave the own king in check.
+fn pseudo_legal_moves(origin: Position, game: &Game) -> Vec<Move> {
     let piece = match game.piece_at(origin) {
         Some(piece) => piece,
         None => return Vec::new(),
@@ -105,7 +330,7 @@ fn valid_destinations_with_special_cases(origin: Position, game: &Game) -> Vec<M
     match piece.piece_type {
         super::pieces::PieceType::King => {
             let mut destinations = wrap_as_normal(
-                destinations(origin, &Direction::all(), 1, game),
+                destinations(origin, Direction::all(), 1, game),
                 origin,
                 game,
             );
@@ -113,17 +338,17 @@ fn valid_destinations_with_special_cases(origin: Position, game: &Game) -> Vec<M
             destinations
         }
         super::pieces::PieceType::Queen => wrap_as_normal(
-            destinations(origin, &Direction::all(), 7, game),
+            destinations(origin, Direction::all(), 7, game),
             origin,
             game,
         ),
         super::pieces::PieceType::Rook => wrap_as_normal(
-            destinations(origin, &Direction::all_non_diagonal(), 7, game),
+            destinations(origin, Direction::all_non_diagonal(), 7, game),
             origin,
             game,
         ),
         super::pieces::PieceType::Bishop => wrap_as_normal(
-            destinations(origin, &Direction::all_diagonal(), 7, game),
+            destinations(origin, Direction::all_diagonal(), 7, game),
             origin,
             game,
         ),
@@ -132,14 +357,6 @@ fn valid_destinations_with_special_cases(origin: Position, game: &Game) -> Vec<M
         }
         super::pieces::PieceType::Pawn => pawn_destinations(origin, game),
     }
-    .into_iter()
-    .filter(|mov| {
-        !game
-            .perform_move(*mov)
-            .unwrap()
-            .is_king_in_check(piece.color)
-    })
-    .collect()
 }
 
 fn wrap_as_normal(positions: Vec<Position>, origin: Position, game: &Game) -> Vec<Move> {
@@ -157,19 +374,19 @@ fn wrap_as_normal(positions: Vec<Position>, origin: Position, game: &Game) -> Ve
 
 fn castling_destinations(origin: Position, game: &Game) -> Vec<Move> {
     let mut destinations = vec![];
-    let king = game.piece_at(origin).unwrap();
-
-    if king.has_moved {
-        return destinations;
-    }
-
-    let color = king.color;
+    let color = game.piece_at(origin).unwrap().color;
     let expected_pos = match color {
         Color::White => Position::from_str("E1"),
         Color::Black => Position::from_str("E8"),
     };
 
-    if expected_pos != origin {
+    let rights = game.castling_rights();
+    if expected_pos != origin || !rights.kingside(color) && !rights.queenside(color) {
+        return destinations;
+    }
+
+    // castling out of check is not allowed
+    if game.is_king_in_check(color) {
         return destinations;
     }
 
@@ -184,6 +401,10 @@ fn castling_destinations(origin: Position, game: &Game) -> Vec<Move> {
 }
 
 fn castling_left(origin: Position, game: &Game) -> Option<Move> {
+    let color = game.piece_at(origin).unwrap().color;
+    if !game.castling_rights().queenside(color) {
+        return None;
+    }
     if game
         .piece_at(origin.moved(Direction::West, 1).unwrap())
         .is_some()
@@ -202,20 +423,26 @@ fn castling_left(origin: Position, game: &Game) -> Option<Move> {
     {
         return None;
     }
-    if let Some(piece) = game.piece_at(origin.moved(Direction::West, 4).unwrap()) {
-        if piece.piece_type == PieceType::Rook && !piece.has_moved {
-            return Some(Move::Castling(Castling {
-                king_origin: origin,
-                king_destination: origin.moved(Direction::West, 2).unwrap(),
-                rook_origin: origin.moved(Direction::West, 4).unwrap(),
-                rook_destination: origin.moved(Direction::West, 1).unwrap(),
-            }));
-        }
+    if is_passing_square_attacked(origin, Direction::West, game) {
+        return None;
+    }
+    let rook_pos = origin.moved(Direction::West, 4).unwrap();
+    if !is_own_rook(rook_pos, color, game) {
+        return None;
     }
-    None
+    Some(Move::Castling(Castling {
+        king_origin: origin,
+        king_destination: origin.moved(Direction::West, 2).unwrap(),
+        rook_origin: rook_pos,
+        rook_destination: origin.moved(Direction::West, 1).unwrap(),
+    }))
 }
 
 fn castling_right(origin: Position, game: &Game) -> Option<Move> {
+    let color = game.piece_at(origin).unwrap().color;
+    if !game.castling_rights().kingside(color) {
+        return None;
+    }
     if game
         .piece_at(origin.moved(Direction::East, 1).unwrap())
         .is_some()
@@ -228,17 +455,37 @@ fn castling_right(origin: Position, game: &Game) -> Option<Move> {
     {
         return None;
     }
-    if let Some(piece) = game.piece_at(origin.moved(Direction::East, 3).unwrap()) {
-        if piece.piece_type == PieceType::Rook && !piece.has_moved {
-            return Some(Move::Castling(Castling {
-                king_origin: origin,
-                king_destination: origin.moved(Direction::East, 2).unwrap(),
-                rook_origin: origin.moved(Direction::East, 3).unwrap(),
-                rook_destination: origin.moved(Direction::East, 1).unwrap(),
-            }));
-        }
+    if is_passing_square_attacked(origin, Direction::East, game) {
+        return None;
+    }
+    let rook_pos = origin.moved(Direction::East, 3).unwrap();
+    if !is_own_rook(rook_pos, color, game) {
+        return None;
     }
-    None
+    Some(Move::Castling(Castling {
+        king_origin: origin,
+        king_destination: origin.moved(Direction::East, 2).unwrap(),
+        rook_origin: rook_pos,
+        rook_destination: origin.moved(Direction::East, 1).unwrap(),
+    }))
+}
+
+fn is_own_rook(pos: Position, color: Color, game: &Game) -> bool {
+    game.piece_at(pos)
+        .is_some_and(|piece| piece.piece_type == PieceType::Rook && piece.color == color)
+}
+
+/// Whether the king would be in check on the square it passes while castling. The destination
+/// square is checked like for every other move.
+fn is_passing_square_attacked(origin: Position, dir: Direction, game: &Game) -> bool {
+    let color = game.piece_at(origin).unwrap().color;
+    game.perform_move(Move::NormalMove(NormalMove {
+        origin,
+        destination: origin.moved(dir, 1).unwrap(),
+        throwing: None,
+    }))
+    .unwrap()
+    .is_king_in_check(color)
 }
 
 fn pawn_destinations(origin: Position, game: &Game) -> Vec<Move> {
@@ -253,11 +500,7 @@ fn pawn_destinations(origin: Position, game: &Game) -> Vec<Move> {
     if let Some(one_step_forward) = origin.moved(dir, 1) {
         match game.piece_at(one_step_forward) {
             None => {
-                destinations.push(Move::NormalMove(NormalMove {
-                    origin,
-                    destination: one_step_forward,
-                    throwing: None,
-                }));
+                destinations.append(&mut pawn_moves(origin, one_step_forward, None, color));
 
                 if !has_moved {
                     if let Some(two_step_forward) = origin.moved(dir, 2) {
@@ -276,42 +519,41 @@ fn pawn_destinations(origin: Position, game: &Game) -> Vec<Move> {
         };
     }
 
-    for side_dir in vec![Direction::West, Direction::East] {
+    for side_dir in [Direction::West, Direction::East] {
         if let Some(forward_and_side) = origin.moved(dir, 1).and_then(|p| p.moved(side_dir, 1)) {
             match game.piece_at(forward_and_side) {
                 None => {}
                 Some(piece) if piece.color == color => {}
                 Some(piece) if piece.color != color => {
-                    destinations.push(Move::NormalMove(NormalMove {
+                    destinations.append(&mut pawn_moves(
                         origin,
-                        destination: forward_and_side,
-                        throwing: Some(piece),
-                    }));
+                        forward_and_side,
+                        Some(piece),
+                        color,
+                    ));
                 }
                 _ => unreachable!(),
             };
         }
     }
 
-    for side_dir in vec![Direction::West, Direction::East] {
+    for side_dir in [Direction::West, Direction::East] {
         if let Some(side_pos) = origin.moved(side_dir, 1) {
             if let Some(piece) = game.piece_at(side_pos) {
                 if piece.piece_type != PieceType::Pawn || piece.color == color {
                     continue;
                 }
 
-                // Safety: if there is an enemy pawn next to one of our pawns, moves must have happened
-                if let Move::NormalMove(normal_move) = game.last_move.unwrap() {
-                    // Safety: checked existence of position next to us before with the if let
-                    if normal_move.destination == side_pos
-                        && (normal_move.destination.y as i8 - normal_move.origin.y as i8).abs() == 2
-                    {
-                        destinations.push(Move::EnPassante(EnPassante {
-                            origin,
-                            destination: side_pos.moved(dir, 1).unwrap(),
-                            throwing: (side_pos, piece),
-                        }));
-                    }
+                // the pawn next to us just passed over the square behind it
+                if let Some(destination) = game
+                    .en_passant_target()
+                    .filter(|&target| side_pos.moved(dir, 1) == Some(target))
+                {
+                    destinations.push(Move::EnPassante(EnPassante {
+                        origin,
+                        destination,
+                        throwing: (side_pos, piece),
+                    }));
                 }
             }
         }
@@ -320,41 +562,67 @@ fn pawn_destinations(origin: Position, game: &Game) -> Vec<Move> {
     destinations
 }
 
+/// The move of a pawn to `destination`, or one move for each piece it can be promoted to if the
+/// destination is on the last rank.
+fn pawn_moves(
+    origin: Position,
+    destination: Position,
+    throwing: Option<Piece>,
+    color: Color,
+) -> Vec<Move> {
+    let last_rank = match color {
+        Color::White => 7,
+        Color::Black => 0,
+    };
+    if destination.y != last_rank {
+        return vec![Move::NormalMove(NormalMove {
+            origin,
+            destination,
+            throwing,
+        })];
+    }
+    [
+        PieceType::Queen,
+        PieceType::Rook,
+        PieceType::Bishop,
+        PieceType::Knight,
+    ]
+    .into_iter()
+    .map(|piece_type| {
+        Move::Promotion(Promotion {
+            origin,
+            destination,
+            new_piece: Piece::new(piece_type, color),
+            throwing,
+        })
+    })
+    .collect()
+}
+
 fn destinations(
     origin: Position,
     directions: &[Direction],
     max_steps: i8,
     game: &Game,
 ) -> Vec<Position> {
-    directions
-        .iter()
-        .flat_map(|dir| {
-            match (1..=max_steps)
-                .filter_map(|distance| origin.moved(*dir, distance))
-                .try_fold(vec![], |acc, pos| {
-                    let color = game.piece_at(origin).unwrap().color;
-                    let positions = match is_valid_destination(pos, color, game) {
-                        true => {
-                            let mut vec = acc.clone();
-                            vec.push(pos);
-                            vec
-                        }
-                        false => return ControlFlow::Break(acc),
-                    };
-                    match is_enemy_at_destination(pos, color, game) {
-                        true => ControlFlow::Break(positions),
-                        false => ControlFlow::Continue(positions),
-                    }
-                }) {
-                ControlFlow::Continue(positions) => positions,
-                ControlFlow::Break(positions) => positions,
+    let color = game.piece_at(origin).unwrap().color;
+    let mut positions = vec![];
+    for dir in directions {
+        for pos in (1..=max_steps).map_while(|distance| origin.moved(*dir, distance)) {
+            if !is_valid_destination(pos, color, game) {
+                break;
             }
-        })
-        .filter(|pos| is_valid_destination(*pos, game.piece_at(origin).unwrap().color, game))
-        .collect()
+            positions.push(pos);
+            if is_enemy_at_destination(pos, color, game) {
+                break;
+            }
+        }
+    }
+    positions
 }
 
 fn knight_destinations(origin: Position, game: &Game) -> Vec<Position> {
+    let color = game.piece_at(origin).unwrap().color;
     let dirs = Direction::all_non_diagonal();
     dirs.iter()
         .flat_map(|first_dir| {
@@ -367,7 +635,7 @@ fn knight_destinations(origin: Position, game: &Game) -> Vec<Position> {
                     .and_then(|pos| pos.moved(*second_dir, 1))
             })
         })
-        .filter(|pos| is_valid_destination(*pos, game.piece_at(origin).unwrap().color, game))
+        .filter(|pos| is_valid_destination(*pos, color, game))
         .collect()
 }
 
@@ -0,0 +1,127 @@
+//! Helpers to set up exact positions, for tests of this crate and of code built on it.
+
+use std::collections::HashMap;
+
+use super::coordinates::Position;
+use super::game::CastlingRights;
+use super::game::Game;
+use super::moves::MoveRequest;
+use super::pieces::Color;
+use super::pieces::Color::*;
+use super::pieces::Piece;
+use super::pieces::PieceType::*;
+
+/// Builds a `Game` piece by piece. Squares are given as text like "e4" or "E4".
+///
+/// ```
+/// use chess::gamelogic::{pieces::{Color::*, Piece, PieceType::*}, testing::GameBuilder};
+///
+/// let game = GameBuilder::empty()
+///     .with(Piece::new(King, White), "e1")
+///     .with(Piece::new(King, Black), "e8")
+///     .to_move(Black)
+///     .build();
+/// ```
+#[derive(Debug, Clone)]
+pub struct GameBuilder {
+    pieces: HashMap<Position, Piece>,
+    active_color: Color,
+    castling_rights: Option<CastlingRights>,
+}
+
+impl GameBuilder {
+    /// Empty board with white to move.
+    pub fn empty() -> Self {
+        Self {
+            pieces: HashMap::new(),
+            active_color: White,
+            castling_rights: None,
+        }
+    }
+
+    /// Only the white king on E1 and the black king on E8, white to move.
+    pub fn kings() -> Self {
+        Self::empty()
+            .with(Piece::new(King, White), "e1")
+            .with(Piece::new(King, Black), "e8")
+    }
+
+    /// Puts `piece` on `square`, replacing what was there.
+    pub fn with(mut self, piece: Piece, square: &str) -> Self {
+        self.pieces.insert(square_at(square), piece);
+        self
+    }
+
+    /// Puts `piece` on `square` as if it had moved before, so a king or rook cannot castle.
+    pub fn with_moved(self, piece: Piece, square: &str) -> Self {
+        self.with(
+            Piece {
+                has_moved: true,
+                ..piece
+            },
+            square,
+        )
+    }
+
+    pub fn to_move(mut self, color: Color) -> Self {
+        self.active_color = color;
+        self
+    }
+
+    /// Sets the castling rights. Without this, they follow from the kings and rooks that have
+    /// not moved.
+    pub fn castling_rights(mut self, castling_rights: CastlingRights) -> Self {
+        self.castling_rights = Some(castling_rights);
+        self
+    }
+
+    pub fn build(self) -> Game {
+        match self.castling_rights {
+            Some(castling_rights) => {
+                Game::from_position(self.pieces, self.active_color, castling_rights)
+            }
+            None => Game::from_pieces(self.pieces, self.active_color),
+        }
+    }
+}
+
+/// Position of the square named like "e4" or "E4". Panics for anything else.
+pub fn square_at(square: &str) -> Position {
+    Position::from_str(&square.to_ascii_uppercase())
+}
+
+/// Plays `moves`, pairs of origin and destination squares, one after the other from `game`.
+/// Panics if one of them is not valid.
+pub fn play(game: &Game, moves: &[(&str, &str)]) -> Game {
+    moves
+        .iter()
+        .fold(game.clone(), |game, &(origin, destination)| {
+            game.perform_move_request(MoveRequest::new(
+                square_at(origin),
+                square_at(destination),
+                None,
+            ))
+            .unwrap_or_else(|| panic!("{origin}-{destination} is not valid"))
+        })
+}
+
+/// Black to move is stalemated: the king on H8 has no move and is not in check.
+pub fn stalemate() -> Game {
+    GameBuilder::empty()
+        .with(Piece::new(King, White), "f7")
+        .with(Piece::new(Queen, White), "g6")
+        .with(Piece::new(King, Black), "h8")
+        .to_move(Black)
+        .build()
+}
+
+/// Neither side can checkmate: king and bishop against king and bishop, with both bishops on
+/// light squares.
+pub fn insufficient_material() -> Game {
+    GameBuilder::empty()
+        .with(Piece::new(King, White), "e1")
+        .with(Piece::new(Bishop, White), "d1")
+        .with(Piece::new(King, Black), "e8")
+        .with(Piece::new(Bishop, Black), "c8")
+        .build()
+}
@@ -0,0 +1,150 @@
+use super::{
+    game::Game,
+    moves::{Move, MoveRequest},
+};
+
+/// Index of a node in a [`GameTree`].
+pub type NodeId = usize;
+
+/// A position in a [`GameTree`]. Only the annotations can be changed, the moves and positions are
+/// kept consistent by the tree.
+#[derive(Debug, Clone)]
+pub struct Node {
+    mov: Option<Move>,
+    game: Game,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+    pub comment: Option<String>,
+    /// Evaluation of the position in centipawns from the perspective of White
+    pub eval: Option<i32>,
+}
+
+impl Node {
+    /// The move leading to this node. None for the root.
+    pub fn mov(&self) -> Option<Move> {
+        self.mov
+    }
+
+    /// The position after `mov` was played.
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    pub fn parent(&self) -> Option<NodeId> {
+        self.parent
+    }
+
+    /// Continuations from this position. The first child is the main line, the others are
+    /// variations.
+    pub fn children(&self) -> &[NodeId] {
+        &self.children
+    }
+}
+
+/// Tree of games branching off a common starting position, e.g. a played game with its
+/// variations.
+#[derive(Debug, Clone)]
+pub struct GameTree {
+    nodes: Vec<Node>,
+}
+
+impl GameTree {
+    pub fn new(game: Game) -> Self {
+        Self {
+            nodes: vec![Node {
+                mov: None,
+                game,
+                parent: None,
+                children: Vec::new(),
+                comment: None,
+                eval: None,
+            }],
+        }
+    }
+
+    pub fn root(&self) -> NodeId {
+        0
+    }
+
+    pub fn node(&self, id: NodeId) -> &Node {
+        &self.nodes[id]
+    }
+
+    /// The node with the given id, to change its annotations.
+    pub fn node_mut(&mut self, id: NodeId) -> &mut Node {
+        &mut self.nodes[id]
+    }
+
+    /// Plays the requested move in the position of `parent`. If the move already exists as
+    /// continuation, its node is returned instead of creating a new variation. Returns None if the
+    /// move is invalid.
+    pub fn add_move(&mut self, parent: NodeId, move_req: MoveRequest) -> Option<NodeId> {
+        let game = &self.nodes[parent].game;
+        if game
+            .piece_at(move_req.origin)
+            .is_none_or(|piece| piece.color != game.active_color())
+        {
+            return None;
+        }
+        let mov = move_req.to_move(game)?;
+        if let Some(&existing) = self.nodes[parent]
+            .children
+            .iter()
+            .find(|&&child| self.nodes[child].mov == Some(mov))
+        {
+            return Some(existing);
+        }
+
+        let game = self.nodes[parent].game.perform_move(mov)?;
+        let id = self.nodes.len();
+        self.nodes.push(Node {
+            mov: Some(mov),
+            game,
+            parent: Some(parent),
+            children: Vec::new(),
+            comment: None,
+            eval: None,
+        });
+        self.nodes[parent].children.push(id);
+        Some(id)
+    }
+
+    /// Nodes from the root to `id`, both included.
+    pub fn path(&self, id: NodeId) -> Vec<NodeId> {
+        let mut path = vec![id];
+        while let Some(parent) = self.nodes[*path.last().unwrap()].parent {
+            path.push(parent);
+        }
+        path.reverse();
+        path
+    }
+
+    /// Nodes following the first continuation from `id` on, including `id` itself.
+    pub fn main_line(&self, id: NodeId) -> Vec<NodeId> {
+        let mut line = vec![id];
+        while let Some(&child) = self.nodes[*line.last().unwrap()].children.first() {
+            line.push(child);
+        }
+        line
+    }
+
+    /// Makes the variation ending in `id` the main line at every branching point above it.
+    pub fn promote(&mut self, id: NodeId) {
+        let mut current = id;
+        while let Some(parent) = self.nodes[current].parent {
+            let children = &mut self.nodes[parent].children;
+            if let Some(index) = children.iter().position(|&child| child == current) {
+                children[..=index].rotate_right(1);
+            }
+            current = parent;
+        }
+    }
+
+    /// Detaches the node and its subtree from the tree. The node ids of the remaining nodes stay
+    /// valid. The root cannot be removed.
+    pub fn remove(&mut self, id: NodeId) {
+        if let Some(parent) = self.nodes[id].parent {
+            self.nodes[parent].children.retain(|&child| child != id);
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use bevy::prelude::*;
+use std::f32::consts::PI;
+
+use crate::{
+    SelectionChangedEvent,
+    gamelogic::{game::Game, moves::Move},
+};
+
+/// How the valid destinations of the selected piece are shown.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum HighlightStyle {
+    /// Animated marker floating above the destination, or above the piece to capture
+    #[default]
+    Floating,
+    /// Flat dot on quiet destinations, ring around pieces to capture
+    Dots,
+    /// Tinted square, with a different color for captures
+    SquareTint,
+}
+
+impl HighlightStyle {
+    fn next(self) -> Self {
+        match self {
+            Self::Floating => Self::Dots,
+            Self::Dots => Self::SquareTint,
+            Self::SquareTint => Self::Floating,
+        }
+    }
+}
+
+/// Colors possible moves by what they do (see `MoveKind`) to help beginners. Uses flat highlights,
+/// the floating style falls back to dots.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BeginnerAssist(pub bool);
+
+/// What a possible move does, as shown by the color of its highlight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveKind {
+    Quiet,
+    Capture,
+    /// The move checks the opponent king
+    Check,
+    /// The moved piece could be captured right after the move
+    Unsafe,
+}
+
+impl MoveKind {
+    /// Classifies `mov` in `game`. Without `assist`, only captures are told apart.
+    pub fn of(mov: Move, game: &Game, assist: bool) -> Self {
+        let capture = mov.is_capture();
+        let after = match assist.then(|| game.perform_move(mov)).flatten() {
+            Some(after) => after,
+            None if capture => return Self::Capture,
+            None => return Self::Quiet,
+        };
+        if after.is_king_in_check(after.active_color()) {
+            Self::Check
+        } else if after.is_capturable(mov.destination()) {
+            Self::Unsafe
+        } else if capture {
+            Self::Capture
+        } else {
+            Self::Quiet
+        }
+    }
+}
+
+/// Highlight lying flat on the board, used by all styles except `HighlightStyle::Floating`.
+#[derive(Component)]
+pub struct FlatMoveHighlight {}
+
+/// Meshes and materials of the flat highlight styles.
+#[derive(Resource)]
+pub struct HighlightAssets {
+    dot: Handle<Mesh>,
+    ring: Handle<Mesh>,
+    square: Handle<Mesh>,
+    quiet: Handle<StandardMaterial>,
+    capture: Handle<StandardMaterial>,
+    check: Handle<StandardMaterial>,
+    unsafe_square: Handle<StandardMaterial>,
+}
+
+impl FromWorld for HighlightAssets {
+    fn from_world(world: &mut World) -> Self {
+        let (dot, ring, square) = {
+            let mut meshes = world.resource_mut::<Assets<Mesh>>();
+            (
+                meshes.add(Circle::new(0.3)),
+                meshes.add(Annulus::new(0.8, 0.95)),
+                meshes.add(Rectangle::new(2., 2.)),
+            )
+        };
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        let mut material = |color: Color| {
+            materials.add(StandardMaterial {
+                base_color: color,
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                ..default()
+            })
+        };
+
+        Self {
+            dot,
+            ring,
+            square,
+            quiet: material(Color::srgba(0.2, 0.8, 0.3, 0.6)),
+            capture: material(Color::srgba(0.9, 0.2, 0.2, 0.6)),
+            check: material(Color::srgba(0.2, 0.4, 0.95, 0.6)),
+            unsafe_square: material(Color::srgba(0.95, 0.6, 0.1, 0.6)),
+        }
+    }
+}
+
+/// Spawns a flat highlight on the given square entity. Occupied squares get a ring instead of a
+/// dot.
+pub fn spawn_flat_highlight(
+    commands: &mut Commands,
+    style: HighlightStyle,
+    assets: &HighlightAssets,
+    square: Entity,
+    occupied: bool,
+    kind: MoveKind,
+) {
+    let mesh = match (style, occupied) {
+        (HighlightStyle::SquareTint, _) => &assets.square,
+        (_, false) => &assets.dot,
+        (_, true) => &assets.ring,
+    };
+    let material = match kind {
+        MoveKind::Quiet => &assets.quiet,
+        MoveKind::Capture => &assets.capture,
+        MoveKind::Check => &assets.check,
+        MoveKind::Unsafe => &assets.unsafe_square,
+    };
+    commands.spawn((
+        Mesh3d(mesh.clone()),
+        MeshMaterial3d(material.clone()),
+        // the 2d meshes face +Z, lay them onto the board
+        Transform::from_xyz(0., 0.02, 0.).with_rotation(Quat::from_axis_angle(Vec3::X, -PI * 0.5)),
+        FlatMoveHighlight {},
+        ChildOf(square),
+    ));
+}
+
+/// Switches to the next highlight style on key press.
+pub fn switch_highlight_style(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut style: ResMut<HighlightStyle>,
+    mut commands: Commands,
+) {
+    if !keys.just_pressed(KeyCode::KeyH) {
+        return;
+    }
+    *style = style.next();
+    // respawn the highlights of the current selection in the new style
+    commands.trigger(SelectionChangedEvent {});
+}
+
+/// Toggles the beginner assist on key press.
+pub fn toggle_beginner_assist(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut assist: ResMut<BeginnerAssist>,
+    mut commands: Commands,
+) {
+    if !keys.just_pressed(KeyCode::KeyB) {
+        return;
+    }
+    assist.0 = !assist.0;
+    commands.trigger(SelectionChangedEvent {});
+}
@@ -0,0 +1,117 @@
+use bevy::{input::touch::TouchPhase, prelude::*};
+
+use crate::{
+    BoardClickEvent, ChessGame, SelectionChangedEvent, board::BoardLayout,
+    gamelogic::coordinates::Position, pause::GameState,
+};
+
+/// Turns mouse clicks and touches into `BoardClickEvent`s, and handles deselection.
+pub struct InputPlugin;
+
+impl Plugin for InputPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (mouse_input_listener, touch_input_listener).run_if(board_input_allowed),
+        )
+        .add_systems(
+            Update,
+            cancel_selection.run_if(in_state(GameState::Playing)),
+        )
+        .add_observer(raw_click_handler);
+    }
+}
+
+/// Marks UI panels (menus, dialogs, pickers) that take all pointer input while they are open.
+#[derive(Component)]
+pub struct BlocksBoardInput {}
+
+/// Run condition for systems reacting to pointer input on the board: false while a blocking panel
+/// is open or the pointer is over a UI element.
+pub fn board_input_allowed(
+    blockers: Query<(), With<BlocksBoardInput>>,
+    interactions: Query<&Interaction>,
+) -> bool {
+    blockers.is_empty()
+        && interactions
+            .iter()
+            .all(|interaction| *interaction == Interaction::None)
+}
+
+/// Event indicating that the mouse was clicked or the touch pad touched.
+#[derive(Debug, Event)]
+struct RawClickEvent {
+    /// Window coords where the click/touch happened
+    pos: Vec2,
+}
+
+fn mouse_input_listener(
+    mouse_button_input_reader: Res<ButtonInput<MouseButton>>,
+    window: Query<&Window>,
+    mut commands: Commands,
+) {
+    if !mouse_button_input_reader.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    let window = window.single().unwrap();
+    if let Some(pos) = window.cursor_position() {
+        commands.trigger(RawClickEvent { pos });
+    }
+}
+
+/// Deselects the selected piece on right click or Esc.
+pub fn cancel_selection(
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut game: ResMut<ChessGame>,
+    mut commands: Commands,
+) {
+    if game.selected_tile.is_none() {
+        return;
+    }
+    if mouse_buttons.just_pressed(MouseButton::Right) || keys.just_pressed(KeyCode::Escape) {
+        game.select(None);
+        commands.trigger(SelectionChangedEvent {});
+    }
+}
+
+fn touch_input_listener(mut touches: MessageReader<TouchInput>, mut commands: Commands) {
+    for touch in touches.read() {
+        if touch.phase == TouchPhase::Started {
+            commands.trigger(RawClickEvent {
+                pos: touch.position,
+            });
+        }
+    }
+}
+
+/// Takes raw input (mouse clicks, touch) and converts to chessboard coordinates.
+fn raw_click_handler(
+    event: On<RawClickEvent>,
+    mut commands: Commands,
+    camera: Query<(&Camera, &GlobalTransform)>,
+    layout: Res<BoardLayout>,
+) {
+    let (camera, camera_transform) = camera.single().unwrap();
+    let board_pos = window_to_board(camera, camera_transform, &layout, event.pos);
+    commands.trigger(BoardClickEvent { board_pos });
+}
+
+/// Converts window coordinates to the position on the chessboard under them. None if they are
+/// outside the chessboard.
+pub fn window_to_board(
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    layout: &BoardLayout,
+    pos: Vec2,
+) -> Option<Position> {
+    let ray = camera.viewport_to_world(camera_transform, pos).ok()?;
+    if ray.direction.y > -0.0001 {
+        // we are not looking down
+        // cannot happen with fixed camera
+        return None;
+    }
+    let t = (layout.origin.y - ray.origin.y) / ray.direction.y;
+    layout.from_world(ray.origin + ray.direction * t)
+}
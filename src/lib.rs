@@ -0,0 +1,314 @@
+pub mod gamelogic;
+
+pub mod asset_packs;
+pub mod board;
+pub mod camera;
+pub mod capture;
+pub mod console;
+pub mod diagnostics;
+pub mod draw_claim;
+pub mod highlights;
+pub mod input;
+pub mod log_buffer;
+pub mod mate_solver;
+pub mod move_hint;
+pub mod pause;
+pub mod preview;
+pub mod render_sync;
+pub mod tuning;
+pub mod ui;
+
+use bevy::prelude::*;
+use board::BoardLayout;
+use capture::{CaptureStyle, KnockedOver};
+use gamelogic::{
+    coordinates::Position,
+    game::Game,
+    moves,
+    pieces::Piece,
+    tree::{GameTree, NodeId},
+};
+use preview::Translucent;
+
+/// Everything needed to play chess in a window: the game state, input handling, rendering of the
+/// board and the menus. Has to be added after the `DefaultPlugins`.
+pub struct ChessPlugin;
+
+impl Plugin for ChessPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            GamePlugin,
+            input::InputPlugin,
+            render_sync::RenderSyncPlugin,
+            ui::UiPlugin,
+        ));
+    }
+}
+
+/// The game state and the handling of board clicks and moves. Needs neither a window nor a
+/// renderer, so it can run headless.
+pub struct GamePlugin;
+
+impl Plugin for GamePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ChessGame>()
+            .init_resource::<BoardLayout>()
+            .init_resource::<CaptureStyle>()
+            .add_observer(board_click_handler)
+            .add_observer(try_move_handler)
+            .add_observer(check_winner)
+            .add_observer(successful_move_handler);
+    }
+}
+
+/// The game being played and the selection of the player.
+#[derive(Resource)]
+pub struct ChessGame {
+    /// The current position, the one of `node`
+    pub game: Game,
+    pub selected_tile: Option<Position>,
+    /// Valid moves of the piece on `selected_tile`, cached when the selection changes
+    pub possible_moves: Vec<moves::Move>,
+    /// The moves played so far, and the ones taken back
+    tree: GameTree,
+    node: NodeId,
+}
+
+impl Default for ChessGame {
+    fn default() -> Self {
+        Self::new(Game::new())
+    }
+}
+
+impl ChessGame {
+    /// Starts playing from `game`.
+    pub fn new(game: Game) -> Self {
+        let tree = GameTree::new(game.clone());
+        Self {
+            game,
+            selected_tile: None,
+            possible_moves: Vec::new(),
+            node: tree.root(),
+            tree,
+        }
+    }
+
+    pub fn select(&mut self, tile: Option<Position>) {
+        self.selected_tile = tile;
+        self.possible_moves.clear();
+        if let Some(pos) = tile {
+            self.possible_moves
+                .extend(moves::valid_moves(pos, &self.game));
+        }
+    }
+
+    /// The moves played so far, oldest first.
+    pub fn moves(&self) -> Vec<moves::Move> {
+        self.tree
+            .path(self.node)
+            .into_iter()
+            .filter_map(|id| self.tree.node(id).mov())
+            .collect()
+    }
+
+    /// Goes back to the position before the last move and clears the selection. Returns false if
+    /// no move was played.
+    pub fn take_back(&mut self) -> bool {
+        let Some(parent) = self.tree.node(self.node).parent() else {
+            return false;
+        };
+        self.node = parent;
+        self.game = self.tree.node(parent).game().clone();
+        self.select(None);
+        true
+    }
+}
+
+/// Model of a piece, standing on `pos`.
+#[derive(Component)]
+pub struct PieceMarker {
+    pub pos: Position,
+}
+
+/// Model of a pawn that has been promoted and still has to be replaced by the model of `piece`.
+#[derive(Component)]
+pub struct Promoted {
+    pub piece: Piece,
+}
+
+/// Event indicating a click or a touch, in chess board coordinates.
+#[derive(Debug, Event)]
+pub struct BoardClickEvent {
+    /// The click position in board coord. If None, the click was outside the chessboard.
+    pub board_pos: Option<Position>,
+}
+
+#[derive(Event)]
+pub struct SelectionChangedEvent {}
+
+/// Ends the current game and starts a new one.
+#[derive(Event)]
+pub struct NewGameEvent {}
+
+/// The game was replaced other than by a move, e.g. by a new game or by taking a move back. The
+/// models have to be put onto the squares of its pieces again.
+#[derive(Event)]
+pub struct GameReplacedEvent {}
+
+#[derive(Event)]
+pub struct TryMoveEvent {
+    pub origin: Position,
+    pub destination: Position,
+    /// Piece a pawn reaching the last rank turns into, a queen if None
+    pub promotion: Option<Piece>,
+}
+
+fn try_move_handler(event: On<TryMoveEvent>, mut game: ResMut<ChessGame>, mut commands: Commands) {
+    let move_req = moves::MoveRequest::new(event.origin, event.destination, event.promotion);
+    match move_req.check(&game.game) {
+        Ok(_) => {
+            let node = game.node;
+            if let Some(next) = game.tree.add_move(node, move_req) {
+                info!(
+                    "{:?} played {}-{}",
+                    game.game.active_color(),
+                    event.origin,
+                    event.destination
+                );
+                game.node = next;
+                game.game = game.tree.node(next).game().clone();
+                debug!("Position after the move:\n{}", game.game);
+                commands.trigger(SuccessfulMoveEvent {});
+            }
+        }
+        Err(error) => {
+            debug!(
+                "Illegal move {}-{}: {}",
+                event.origin, event.destination, error
+            );
+            commands.trigger(IllegalMoveEvent { error });
+        }
+    }
+}
+
+/// A requested move could not be performed.
+#[derive(Event)]
+pub struct IllegalMoveEvent {
+    pub error: moves::MoveError,
+}
+
+#[derive(Event)]
+pub struct SuccessfulMoveEvent {}
+
+fn check_winner(_: On<SuccessfulMoveEvent>, game: Res<ChessGame>) {
+    if let Some(winner) = game.game.winner() {
+        info!("The winner is {:?}", winner);
+    } else if game.game.is_stalemate() {
+        info!("Draw by stalemate");
+    }
+}
+
+fn successful_move_handler(
+    _: On<SuccessfulMoveEvent>,
+    game: Res<ChessGame>,
+    capture_style: Res<CaptureStyle>,
+    layout: Res<BoardLayout>,
+    mut commands: Commands,
+    mut pieces: Query<(Entity, &mut PieceMarker)>,
+) {
+    // Safety: We are in successful_move_handler, so there has to be a last move.
+    let last_move = game.game.last_move.unwrap();
+    let moves = match last_move {
+        moves::Move::NormalMove(normal_move) => {
+            vec![(normal_move.origin, normal_move.destination)]
+        }
+        moves::Move::EnPassante(en_passante) => {
+            vec![(en_passante.origin, en_passante.destination)]
+        }
+        moves::Move::Castling(castling) => vec![
+            (castling.king_origin, castling.king_destination),
+            (castling.rook_origin, castling.rook_destination),
+        ],
+        moves::Move::Promotion(promotion) => vec![(promotion.origin, promotion.destination)],
+    };
+    let thrown = match last_move {
+        moves::Move::NormalMove(normal_move) => {
+            normal_move.throwing.map(|_| normal_move.destination)
+        }
+        moves::Move::EnPassante(en_passante) => Some(en_passante.throwing.0),
+        moves::Move::Castling(_) => None,
+        moves::Move::Promotion(promotion) => promotion.throwing.map(|_| promotion.destination),
+    };
+
+    if let Some(throw_pos) = thrown {
+        let (origin, destination) = moves[0];
+        let direction = layout.to_world(destination) - layout.to_world(origin);
+        for (entity, marker) in pieces.iter() {
+            if marker.pos != throw_pos {
+                continue;
+            }
+            match *capture_style {
+                CaptureStyle::Vanish => commands.entity(entity).despawn(),
+                CaptureStyle::KnockOver => {
+                    commands
+                        .entity(entity)
+                        .remove::<(PieceMarker, Translucent)>()
+                        .insert(KnockedOver::new(direction));
+                }
+            }
+        }
+    }
+    for (entity, mut marker) in pieces.iter_mut() {
+        for &(origin, destination) in moves.iter() {
+            if marker.pos == origin {
+                marker.pos = destination;
+                if let moves::Move::Promotion(promotion) = last_move {
+                    commands.entity(entity).insert(Promoted {
+                        piece: promotion.new_piece,
+                    });
+                }
+            }
+        }
+    }
+}
+
+fn board_click_handler(
+    event: On<BoardClickEvent>,
+    mut game: ResMut<ChessGame>,
+    mut commands: Commands,
+) {
+    let selected_movable = event.board_pos.and_then(|pos| {
+        game.game
+            .piece_at(pos)
+            .and_then(|piece| {
+                if piece.color == game.game.active_color() {
+                    Some(())
+                } else {
+                    None
+                }
+            })
+            .map(|_| pos)
+    });
+
+    if selected_movable == game.selected_tile {
+        // click on same tile as last time, nothing today
+        return;
+    }
+
+    if selected_movable.is_some() {
+        // clicked on friendly field, showing possible moves
+        game.select(selected_movable);
+        commands.trigger(SelectionChangedEvent {});
+    } else if let (Some(origin), Some(destination)) = (game.selected_tile, event.board_pos) {
+        // previously selected a tile, now clicked on another field. Try to do the move.
+        commands.trigger(TryMoveEvent {
+            origin,
+            destination,
+            promotion: None,
+        });
+        // either the move succeeds and the board changes or the user clicked on a tile that is
+        // unreachable for the selected piece. In both cases, we deselect the current tile.
+        game.select(None);
+        commands.trigger(SelectionChangedEvent {});
+    }
+}
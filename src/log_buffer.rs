@@ -0,0 +1,82 @@
+use bevy::{
+    log::{
+        BoxedLayer,
+        tracing::{
+            Event, Subscriber,
+            field::{Field, Visit},
+        },
+        tracing_subscriber::{Layer, layer::Context},
+    },
+    prelude::*,
+};
+use std::{
+    collections::VecDeque,
+    fmt::Write,
+    sync::{Arc, Mutex},
+};
+
+/// Number of log lines kept, older ones are dropped.
+const CAPACITY: usize = 200;
+
+/// The most recent log messages, shown by the `log` console command. Filled by the layer of
+/// `log_layer`, so it only sees messages that pass the filter of the `LogPlugin`.
+#[derive(Resource, Debug, Default, Clone)]
+pub struct LogBuffer(Arc<Mutex<VecDeque<String>>>);
+
+impl LogBuffer {
+    fn push(&self, line: String) {
+        let mut lines = self.0.lock().unwrap();
+        if lines.len() == CAPACITY {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+
+    /// The last `count` lines, oldest first.
+    pub fn recent(&self, count: usize) -> Vec<String> {
+        let lines = self.0.lock().unwrap();
+        let skipped = lines.len().saturating_sub(count);
+        lines.iter().skip(skipped).cloned().collect()
+    }
+
+    /// A tracing layer that writes every event into this buffer.
+    pub fn layer(&self) -> LogBufferLayer {
+        LogBufferLayer {
+            buffer: self.clone(),
+        }
+    }
+}
+
+/// Tracing layer that formats events as `LEVEL target: message` and stores them in a `LogBuffer`.
+pub struct LogBufferLayer {
+    buffer: LogBuffer,
+}
+
+impl<S: Subscriber> Layer<S> for LogBufferLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut line = format!("{} {}:", metadata.level(), metadata.target());
+        event.record(&mut LineVisitor(&mut line));
+        self.buffer.push(line);
+    }
+}
+
+/// Appends the message and the other fields of an event to a line.
+struct LineVisitor<'a>(&'a mut String);
+
+impl Visit for LineVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.0, " {:?}", value);
+        } else {
+            let _ = write!(self.0, " {}={:?}", field.name(), value);
+        }
+    }
+}
+
+/// For `LogPlugin::custom_layer`: inserts a `LogBuffer` and returns the layer filling it.
+pub fn log_layer(app: &mut App) -> Option<BoxedLayer> {
+    let buffer = LogBuffer::default();
+    app.insert_resource(buffer.clone());
+    Some(Box::new(buffer.layer()))
+}
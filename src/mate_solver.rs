@@ -0,0 +1,38 @@
+use bevy::prelude::*;
+
+use crate::{
+    ChessGame,
+    move_hint::{self, MoveHint},
+};
+
+/// Maximum number of moves of the mates searched for. The search is exhaustive and runs in the
+/// frame the key is pressed, deeper searches would freeze the game noticeably.
+const MATE_SEARCH_DEPTH: u32 = 2;
+
+/// Searches for forced mates of the player to move on key press and shows all key moves.
+pub fn show_mating_moves(
+    keys: Res<ButtonInput<KeyCode>>,
+    game: Res<ChessGame>,
+    mut commands: Commands,
+    hints: Query<Entity, With<MoveHint>>,
+) {
+    if !keys.just_pressed(KeyCode::KeyM) {
+        return;
+    }
+    let key_moves: Vec<String> = game
+        .game
+        .mating_moves(MATE_SEARCH_DEPTH)
+        .iter()
+        .map(|mov| format!("{}-{}", mov.origin(), mov.destination()))
+        .collect();
+    let text = if key_moves.is_empty() {
+        format!("No mate in {MATE_SEARCH_DEPTH} or less")
+    } else {
+        format!(
+            "Mate in {MATE_SEARCH_DEPTH} or less: {}",
+            key_moves.join(", ")
+        )
+    };
+    info!("{text}");
+    move_hint::spawn_hint(&mut commands, &hints, text);
+}
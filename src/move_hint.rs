@@ -0,0 +1,69 @@
+use bevy::prelude::*;
+
+use crate::IllegalMoveEvent;
+
+/// How long the explanation of an illegal move stays visible, in seconds.
+const HINT_DURATION: f32 = 3.;
+
+/// Short text at the bottom of the window, e.g. explaining why the last requested move was illegal.
+#[derive(Component)]
+pub struct MoveHint {
+    timer: Timer,
+}
+
+/// Shows why a move was illegal, replacing the previous explanation.
+pub fn show_move_hint(
+    event: On<IllegalMoveEvent>,
+    mut commands: Commands,
+    hints: Query<Entity, With<MoveHint>>,
+) {
+    let mut text = event.error.to_string();
+    // capitalize the first letter, the message starts a sentence here
+    if let Some(first) = text.get_mut(0..1) {
+        first.make_ascii_uppercase();
+    }
+    spawn_hint(&mut commands, &hints, text);
+}
+
+/// Shows `text` for a few seconds, replacing the previous hint.
+pub fn spawn_hint(
+    commands: &mut Commands,
+    hints: &Query<Entity, With<MoveHint>>,
+    text: impl Into<String>,
+) {
+    for entity in hints {
+        commands.entity(entity).despawn();
+    }
+    commands.spawn((
+        MoveHint {
+            timer: Timer::from_seconds(HINT_DURATION, TimerMode::Once),
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(24.),
+            width: Val::Percent(100.),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        children![(
+            Text::new(text),
+            TextFont {
+                font_size: 24.,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0., 0., 0., 0.6)),
+        )],
+    ));
+}
+
+pub fn expire_move_hints(
+    mut commands: Commands,
+    mut hints: Query<(Entity, &mut MoveHint)>,
+    time: Res<Time>,
+) {
+    for (entity, mut hint) in &mut hints {
+        if hint.timer.tick(time.delta()).is_finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
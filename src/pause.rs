@@ -0,0 +1,119 @@
+use bevy::prelude::*;
+
+use crate::{ChessGame, NewGameEvent, input::BlocksBoardInput};
+
+#[derive(States, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameState {
+    #[default]
+    Playing,
+    Paused,
+}
+
+#[derive(Component)]
+pub struct PauseMenu {}
+
+#[derive(Component, Debug, Clone, Copy)]
+pub enum PauseMenuButton {
+    Resume,
+    Abandon,
+}
+
+/// Pauses and resumes on Esc. While a piece is selected, Esc cancels the selection instead.
+pub fn toggle_pause(
+    keys: Res<ButtonInput<KeyCode>>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    game: Res<ChessGame>,
+) {
+    if !keys.just_pressed(KeyCode::Escape) {
+        return;
+    }
+    if *state.get() == GameState::Playing && game.selected_tile.is_some() {
+        return;
+    }
+    next_state.set(match state.get() {
+        GameState::Playing => GameState::Paused,
+        GameState::Paused => GameState::Playing,
+    });
+}
+
+/// Stops all animations and shows the pause menu over the dimmed board.
+pub fn enter_pause(mut commands: Commands, mut time: ResMut<Time<Virtual>>) {
+    info!("Game paused");
+    time.pause();
+    commands.spawn((
+        PauseMenu {},
+        BlocksBoardInput {},
+        Node {
+            width: Val::Percent(100.),
+            height: Val::Percent(100.),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(12.),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0., 0., 0., 0.6)),
+        children![
+            (
+                Text::new("Paused"),
+                TextFont {
+                    font_size: 48.,
+                    ..default()
+                },
+            ),
+            menu_button("Resume", PauseMenuButton::Resume),
+            menu_button("Abandon game", PauseMenuButton::Abandon),
+        ],
+    ));
+}
+
+pub fn exit_pause(
+    mut commands: Commands,
+    mut time: ResMut<Time<Virtual>>,
+    menus: Query<Entity, With<PauseMenu>>,
+) {
+    info!("Game resumed");
+    time.unpause();
+    for entity in &menus {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Button of a full screen menu, with `action` identifying it.
+pub fn menu_button(label: &'static str, action: impl Component) -> impl Bundle {
+    (
+        Button,
+        action,
+        Node {
+            width: Val::Px(240.),
+            padding: UiRect::all(Val::Px(10.)),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        BackgroundColor(Color::srgb(0.2, 0.2, 0.2)),
+        children![(
+            Text::new(label),
+            TextFont {
+                font_size: 24.,
+                ..default()
+            },
+        )],
+    )
+}
+
+pub fn pause_menu_buttons(
+    buttons: Query<(&Interaction, &PauseMenuButton), Changed<Interaction>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut commands: Commands,
+) {
+    for (interaction, action) in &buttons {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        if let PauseMenuButton::Abandon = action {
+            commands.trigger(NewGameEvent {});
+        }
+        next_state.set(GameState::Playing);
+    }
+}
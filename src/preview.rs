@@ -0,0 +1,154 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::{
+    ChessGame, PieceMarker,
+    asset_packs::AssetPacks,
+    board::BoardLayout,
+    gamelogic::{coordinates::Position, moves::Move},
+    input::window_to_board,
+    render_sync::{piece_asset, piece_transform},
+};
+
+/// Opacity of the ghost of the selected piece on the hovered destination.
+const GHOST_ALPHA: f32 = 0.4;
+/// Opacity of the piece that would be captured by the previewed move.
+const CAPTURED_ALPHA: f32 = 0.25;
+
+/// Translucent copy of the selected piece, previewing the move to the hovered square.
+#[derive(Component)]
+pub struct GhostPiece {
+    origin: Position,
+    pos: Position,
+}
+
+/// Renders all meshes of the model below this entity with the given opacity.
+#[derive(Component)]
+pub struct Translucent {
+    alpha: f32,
+}
+
+/// Original material of a mesh that is currently rendered translucent.
+#[derive(Component)]
+pub struct OriginalMaterial(Handle<StandardMaterial>);
+
+/// What is needed to place piece models on the board.
+#[derive(SystemParam)]
+pub struct PieceScenes<'w> {
+    asset_server: Res<'w, AssetServer>,
+    asset_packs: Res<'w, AssetPacks>,
+    layout: Res<'w, BoardLayout>,
+}
+
+/// Shows a ghost of the selected piece while the cursor hovers one of its valid destinations.
+pub fn update_move_preview(
+    mut commands: Commands,
+    game: Res<ChessGame>,
+    scenes: PieceScenes,
+    window: Query<&Window>,
+    camera: Query<(&Camera, &GlobalTransform)>,
+    ghosts: Query<(Entity, &GhostPiece)>,
+    pieces: Query<(Entity, &PieceMarker, Has<Translucent>)>,
+) {
+    let (Ok(window), Ok((camera, camera_transform))) = (window.single(), camera.single()) else {
+        return;
+    };
+    let hovered = window
+        .cursor_position()
+        .and_then(|pos| window_to_board(camera, camera_transform, &scenes.layout, pos))
+        .filter(|pos| {
+            game.possible_moves
+                .iter()
+                .any(|mov| mov.destination() == *pos)
+        });
+    // The square the hovered move captures on, which differs from the destination for en passant.
+    let captured = hovered.and_then(|pos| {
+        game.possible_moves
+            .iter()
+            .find(|mov| mov.destination() == pos)
+            .map(|mov| match mov {
+                Move::EnPassante(en_passante) => en_passante.throwing.0,
+                _ => pos,
+            })
+    });
+    let moving_piece = game.selected_tile.and_then(|pos| game.game.piece_at(pos));
+
+    if ghosts
+        .iter()
+        .any(|(_, ghost)| Some(ghost.pos) == hovered && Some(ghost.origin) == game.selected_tile)
+    {
+        return;
+    }
+    for (entity, _) in &ghosts {
+        commands.entity(entity).despawn();
+    }
+    for (entity, marker, translucent) in &pieces {
+        if translucent && Some(marker.pos) != captured {
+            commands.entity(entity).remove::<Translucent>();
+        }
+    }
+
+    let (Some(origin), Some(pos), Some(piece)) = (game.selected_tile, hovered, moving_piece) else {
+        return;
+    };
+    commands.spawn((
+        SceneRoot(
+            scenes
+                .asset_server
+                .load(scenes.asset_packs.scene(piece_asset(piece))),
+        ),
+        piece_transform(&scenes.layout, &scenes.asset_packs, pos, piece),
+        GhostPiece { origin, pos },
+        Translucent { alpha: GHOST_ALPHA },
+    ));
+    for (entity, marker, _) in &pieces {
+        if Some(marker.pos) == captured {
+            commands.entity(entity).insert(Translucent {
+                alpha: CAPTURED_ALPHA,
+            });
+        }
+    }
+}
+
+/// Replaces the materials below `Translucent` entities by translucent copies. Runs every frame, as
+/// scenes are instantiated asynchronously after spawning.
+pub fn apply_translucency(
+    mut commands: Commands,
+    roots: Query<(Entity, &Translucent)>,
+    children: Query<&Children>,
+    mut meshes: Query<&mut MeshMaterial3d<StandardMaterial>, Without<OriginalMaterial>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for (root, translucent) in &roots {
+        for entity in children.iter_descendants(root) {
+            let Ok(mut material) = meshes.get_mut(entity) else {
+                continue;
+            };
+            let Some(mut translucent_material) = materials.get(&material.0).cloned() else {
+                continue;
+            };
+            translucent_material.base_color = translucent_material
+                .base_color
+                .with_alpha(translucent.alpha);
+            translucent_material.alpha_mode = AlphaMode::Blend;
+            let original = std::mem::replace(&mut material.0, materials.add(translucent_material));
+            commands.entity(entity).insert(OriginalMaterial(original));
+        }
+    }
+}
+
+/// Restores the original materials of models that are no longer `Translucent`.
+pub fn restore_opacity(
+    mut commands: Commands,
+    mut removed: RemovedComponents<Translucent>,
+    children: Query<&Children>,
+    mut meshes: Query<(&mut MeshMaterial3d<StandardMaterial>, &OriginalMaterial)>,
+) {
+    for root in removed.read() {
+        for entity in children.iter_descendants(root) {
+            if let Ok((mut material, original)) = meshes.get_mut(entity) {
+                material.0 = original.0.clone();
+                commands.entity(entity).remove::<OriginalMaterial>();
+            }
+        }
+    }
+}
@@ -0,0 +1,491 @@
+use bevy::{
+    camera::primitives::Aabb, ecs::system::SystemParam, prelude::*, scene::SceneInstanceReady,
+};
+use std::f32::consts::PI;
+
+use crate::{
+    ChessGame, GameReplacedEvent, NewGameEvent, PieceMarker, Promoted, SelectionChangedEvent,
+    asset_packs::AssetPacks,
+    board::{self, Board, BoardLayout, BoardModel, TableModel},
+    camera::{self, CameraPreset, CameraRig},
+    capture,
+    gamelogic::{
+        coordinates::Position,
+        game::Game,
+        pieces::{self, Piece, PieceType},
+    },
+    highlights::{
+        self, BeginnerAssist, FlatMoveHighlight, HighlightAssets, HighlightStyle, MoveKind,
+    },
+    input, preview,
+    tuning::{self, Tuning},
+};
+
+/// Spawns the board, the pieces and the camera, and keeps the models in sync with the game.
+pub struct RenderSyncPlugin;
+
+impl Plugin for RenderSyncPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AssetPacks>()
+            .init_resource::<Tuning>()
+            .init_resource::<HighlightStyle>()
+            .init_resource::<BeginnerAssist>()
+            .init_resource::<HighlightAssets>()
+            .add_systems(Startup, initialize_rendering)
+            .add_systems(
+                Update,
+                ((rotate_selected_marker, animate_possible_moves),).chain(),
+            )
+            .add_systems(
+                Update,
+                (
+                    move_light,
+                    move_pieces,
+                    idle_animation,
+                    switch_asset_pack,
+                    show_promoted_pieces,
+                    measure_piece_models,
+                    highlights::switch_highlight_style,
+                    highlights::toggle_beginner_assist,
+                    capture::switch_capture_style,
+                    capture::animate_knocked_over,
+                    camera::toggle_fullscreen,
+                    board::toggle_table,
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    camera::frame_board,
+                    camera::select_camera_preset,
+                    camera::animate_camera,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                preview::update_move_preview.run_if(input::board_input_allowed),
+            )
+            .add_systems(
+                Update,
+                (preview::apply_translucency, preview::restore_opacity),
+            )
+            .add_systems(
+                Update,
+                tuning::reload_tuning.run_if(|| cfg!(debug_assertions)),
+            )
+            .add_observer(forget_model_height)
+            .add_observer(new_selection_handler)
+            .add_observer(new_game_handler)
+            .add_observer(game_replaced_handler);
+    }
+}
+
+#[derive(Component)]
+pub struct PossibleMoveHighlight {
+    base_height: f32,
+    /// Phase offset of the animation, so neighboring highlights do not move in unison
+    individual_offset: f32,
+}
+
+/// File name of the model of the given piece.
+pub fn piece_asset(piece: Piece) -> &'static str {
+    match (piece.piece_type, piece.color) {
+        (PieceType::King, pieces::Color::White) => "king_white.glb",
+        (PieceType::King, pieces::Color::Black) => "king_black.glb",
+        (PieceType::Queen, pieces::Color::White) => "queen_white.glb",
+        (PieceType::Queen, pieces::Color::Black) => "queen_black.glb",
+        (PieceType::Rook, pieces::Color::White) => "rook_white.glb",
+        (PieceType::Rook, pieces::Color::Black) => "rook_black.glb",
+        (PieceType::Bishop, pieces::Color::White) => "bishop_white.glb",
+        (PieceType::Bishop, pieces::Color::Black) => "bishop_black.glb",
+        (PieceType::Knight, pieces::Color::White) => "knight_white.glb",
+        (PieceType::Knight, pieces::Color::Black) => "knight_black.glb",
+        (PieceType::Pawn, pieces::Color::White) => "pawn_white.glb",
+        (PieceType::Pawn, pieces::Color::Black) => "pawn_black.glb",
+    }
+}
+
+fn initialize_rendering(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game: Res<ChessGame>,
+    asset_packs: Res<AssetPacks>,
+    layout: Res<BoardLayout>,
+) {
+    commands.spawn((
+        Camera3d::default(),
+        CameraPreset::White.transform(&layout),
+        CameraRig::new(CameraPreset::White, &layout),
+    ));
+
+    commands.spawn((
+        PointLight {
+            intensity: 5_000_000.0,
+            ..default()
+        },
+        Transform::default(),
+    ));
+
+    board::spawn_board(&mut commands, &asset_server, &asset_packs, &layout);
+
+    spawn_pieces(
+        &mut commands,
+        &asset_server,
+        &game.game,
+        &asset_packs,
+        &layout,
+    );
+}
+
+/// Spawns the models of all pieces of the game.
+pub fn spawn_pieces(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    game: &Game,
+    asset_packs: &AssetPacks,
+    layout: &BoardLayout,
+) {
+    for (pos, piece) in game.pieces() {
+        commands.spawn((
+            SceneRoot(asset_server.load(asset_packs.scene(piece_asset(piece)))),
+            piece_transform(layout, asset_packs, pos, piece),
+            PieceMarker { pos },
+        ));
+    }
+}
+
+/// Scale of the piece models relative to the squares.
+const PIECE_SCALE: f32 = 0.9;
+
+/// Transform of the model of `piece` standing on `pos`.
+pub fn piece_transform(
+    layout: &BoardLayout,
+    asset_packs: &AssetPacks,
+    pos: Position,
+    piece: Piece,
+) -> Transform {
+    Transform::from_translation(layout.to_world(pos))
+        .with_scale(Vec3::splat(PIECE_SCALE * layout.model_scale()))
+        .with_rotation(asset_packs.orientation(piece_asset(piece)))
+}
+
+fn move_light(
+    mut query: Query<&mut Transform, With<PointLight>>,
+    layout: Res<BoardLayout>,
+    time: Res<Time>,
+) {
+    let center = layout.center() + Vec3::new(0., 8., 0.) * layout.model_scale();
+    let distance = 4. * layout.model_scale();
+    let rot = Quat::from_axis_angle(Vec3::Y, time.elapsed_secs() * PI * 0.1);
+    let pos = center + rot.mul_vec3(Vec3::new(0., 0., distance));
+    for mut transform in &mut query {
+        transform.translation = pos;
+    }
+}
+
+fn move_pieces(
+    mut pieces: Query<(&mut Transform, &PieceMarker)>,
+    game: Res<ChessGame>,
+    layout: Res<BoardLayout>,
+    tuning: Res<Tuning>,
+    time: Res<Time>,
+) {
+    for (mut transform, marker) in pieces.iter_mut() {
+        let marker_in_world = layout.to_world(marker.pos).with_y(transform.translation.y);
+        let diff = marker_in_world - transform.translation;
+        let distance = diff.length();
+        let step_distance = (tuning.move_speed * time.delta_secs()).clamp(0., distance);
+        if let Some(step) = diff.try_normalize().map(|v| v * step_distance) {
+            transform.translation += step;
+        }
+
+        let height = if game.selected_tile == Some(marker.pos) {
+            layout.origin.y + tuning.lift_height * layout.model_scale()
+        } else {
+            layout.origin.y
+        };
+        let smoothing = 1. - (-12. * time.delta_secs()).exp();
+        transform.translation.y += (height - transform.translation.y) * smoothing;
+    }
+}
+
+/// Lets the pieces of the side to move breathe slightly.
+fn idle_animation(
+    mut pieces: Query<(&mut Transform, &PieceMarker)>,
+    game: Res<ChessGame>,
+    layout: Res<BoardLayout>,
+    time: Res<Time>,
+) {
+    let active = game.game.active_color();
+    for (mut transform, marker) in pieces.iter_mut() {
+        let breathing = match game.game.piece_at(marker.pos) {
+            Some(piece) if piece.color == active => {
+                let offset = (marker.pos.x + marker.pos.y) as f32 * 0.7;
+                1. + 0.03 * (time.elapsed_secs() * 2. + offset).sin()
+            }
+            _ => 1.,
+        };
+        transform.scale = Vec3::splat(PIECE_SCALE * layout.model_scale() * breathing);
+    }
+}
+
+/// The table, disjoint from the board and the pieces.
+type TableOnly = (With<TableModel>, Without<BoardModel>, Without<PieceMarker>);
+
+/// Switches to the next user asset pack on key press and swaps the models in place.
+fn switch_asset_pack(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut asset_packs: ResMut<AssetPacks>,
+    asset_server: Res<AssetServer>,
+    game: Res<ChessGame>,
+    mut pieces: Query<(&mut SceneRoot, &mut Transform, &PieceMarker), Without<BoardModel>>,
+    mut board: Query<&mut SceneRoot, With<BoardModel>>,
+    mut tables: Query<&mut SceneRoot, TableOnly>,
+) {
+    if !keys.just_pressed(KeyCode::KeyP) {
+        return;
+    }
+
+    asset_packs.cycle();
+    info!(
+        "Using asset pack {}",
+        asset_packs.active_name().unwrap_or("<built-in>")
+    );
+
+    for mut scene in &mut board {
+        scene.0 = asset_server.load(asset_packs.scene("board.glb"));
+    }
+    for mut scene in &mut tables {
+        scene.0 = asset_server.load(asset_packs.scene("table.glb"));
+    }
+    for (mut scene, mut transform, marker) in &mut pieces {
+        if let Some(piece) = game.game.piece_at(marker.pos) {
+            scene.0 = asset_server.load(asset_packs.scene(piece_asset(piece)));
+            transform.rotation = asset_packs.orientation(piece_asset(piece));
+        }
+    }
+}
+
+/// Swaps the models of promoted pawns for the models of the pieces they were promoted to.
+fn show_promoted_pieces(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    asset_packs: Res<AssetPacks>,
+    mut pieces: Query<(Entity, &Promoted, &mut SceneRoot, &mut Transform)>,
+) {
+    for (entity, promoted, mut scene, mut transform) in &mut pieces {
+        scene.0 = asset_server.load(asset_packs.scene(piece_asset(promoted.piece)));
+        transform.rotation = asset_packs.orientation(piece_asset(promoted.piece));
+        commands.entity(entity).remove::<Promoted>();
+    }
+}
+
+/// Height of the top of a piece model above its base, in the units of the model. Lets highlights
+/// float above pieces of any asset pack.
+#[derive(Component)]
+pub struct ModelHeight(pub f32);
+
+/// Marks a piece whose model was (re)spawned and has to be measured.
+#[derive(Component)]
+pub struct Unmeasured {}
+
+fn forget_model_height(
+    event: On<SceneInstanceReady>,
+    mut commands: Commands,
+    pieces: Query<(), With<PieceMarker>>,
+) {
+    if pieces.contains(event.entity) {
+        commands
+            .entity(event.entity)
+            .remove::<ModelHeight>()
+            .insert(Unmeasured {});
+    }
+}
+
+/// Measures the models of spawned pieces from the bounding boxes of their meshes. The boxes are
+/// computed after the scene is spawned, so pieces without them are measured in a later frame.
+fn measure_piece_models(
+    mut commands: Commands,
+    pieces: Query<(Entity, &GlobalTransform), With<Unmeasured>>,
+    children: Query<&Children>,
+    meshes: Query<(&GlobalTransform, &Aabb)>,
+) {
+    for (entity, root) in &pieces {
+        let to_model = root.affine().inverse();
+        let top = children
+            .iter_descendants(entity)
+            .filter_map(|descendant| meshes.get(descendant).ok())
+            .map(|(transform, aabb)| {
+                let to_model = to_model * transform.affine();
+                (0..8)
+                    .map(|corner| {
+                        let sign = Vec3A::new(
+                            if corner & 1 == 0 { -1. } else { 1. },
+                            if corner & 2 == 0 { -1. } else { 1. },
+                            if corner & 4 == 0 { -1. } else { 1. },
+                        );
+                        to_model
+                            .transform_point3a(aabb.center + aabb.half_extents * sign)
+                            .y
+                    })
+                    .fold(f32::MIN, f32::max)
+            })
+            .reduce(f32::max);
+        if let Some(top) = top {
+            commands
+                .entity(entity)
+                .remove::<Unmeasured>()
+                .insert(ModelHeight(top));
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct SelectedMarker {}
+
+fn rotate_selected_marker(mut query: Query<&mut Transform, With<SelectedMarker>>, time: Res<Time>) {
+    for mut transform in &mut query {
+        transform.rotation = Quat::from_axis_angle(Vec3::Y, time.elapsed_secs() * PI * 2.);
+    }
+}
+
+fn animate_possible_moves(
+    mut query: Query<(&mut Transform, &mut PossibleMoveHighlight)>,
+    time: Res<Time>,
+) {
+    for (mut transform, highlight) in &mut query {
+        let individual_offset = highlight.individual_offset;
+        transform.rotation = Quat::from_axis_angle(
+            Vec3::Y,
+            0.25 * PI * ((time.elapsed_secs() + individual_offset) * PI * 0.5).sin(),
+        );
+        transform.translation.y = highlight.base_height
+            + 0.1
+            + 0.1 * ((time.elapsed_secs() + individual_offset) * PI * 1.5).sin();
+    }
+}
+
+/// Floating and flat move highlights.
+type AnyMoveHighlight = Or<(With<PossibleMoveHighlight>, With<FlatMoveHighlight>)>;
+
+/// Assets and settings for showing the selected piece and its possible moves.
+#[derive(SystemParam)]
+pub struct HighlightParams<'w, 's> {
+    asset_server: Res<'w, AssetServer>,
+    asset_packs: Res<'w, AssetPacks>,
+    style: Res<'w, HighlightStyle>,
+    assets: Res<'w, HighlightAssets>,
+    assist: Res<'w, BeginnerAssist>,
+    tuning: Res<'w, Tuning>,
+    pieces: Query<'w, 's, (&'static PieceMarker, &'static ModelHeight)>,
+}
+
+/// Marks the selected piece and highlights the destinations of its possible moves.
+pub fn new_selection_handler(
+    _: On<SelectionChangedEvent>,
+    mut commands: Commands,
+    selected_marker: Query<Entity, With<SelectedMarker>>,
+    highlights: Query<Entity, AnyMoveHighlight>,
+    game: Res<ChessGame>,
+    params: HighlightParams,
+    board: Single<&Board>,
+) {
+    for entity in selected_marker {
+        commands.entity(entity).despawn();
+    }
+    for entity in highlights {
+        commands.entity(entity).despawn();
+    }
+
+    if let Some(pos) = game.selected_tile {
+        commands.spawn((
+            SceneRoot(
+                params
+                    .asset_server
+                    .load(params.asset_packs.scene("selected_piece.glb")),
+            ),
+            SelectedMarker {},
+            ChildOf(board.square(pos)),
+        ));
+    }
+
+    let style = match *params.style {
+        HighlightStyle::Floating if params.assist.0 => HighlightStyle::Dots,
+        style => style,
+    };
+    let handle = params
+        .asset_server
+        .load(params.asset_packs.scene("possible_move.glb"));
+    let mut highlighted: Vec<Position> = vec![];
+    for mov in &game.possible_moves {
+        let pos = mov.destination();
+        if highlighted.contains(&pos) {
+            continue;
+        }
+        highlighted.push(pos);
+        let square = board.square(pos);
+
+        if style != HighlightStyle::Floating {
+            highlights::spawn_flat_highlight(
+                &mut commands,
+                style,
+                &params.assets,
+                square,
+                game.game.piece_at(pos).is_some(),
+                MoveKind::of(*mov, &game.game, params.assist.0),
+            );
+            continue;
+        }
+
+        // float above the measured model, the tuned heights are for models not measured yet
+        let measured = params
+            .pieces
+            .iter()
+            .find(|(marker, _)| marker.pos == pos)
+            .map(|(_, height)| height.0 * PIECE_SCALE + params.tuning.highlight_gap);
+        let base_height = measured.unwrap_or_else(|| {
+            params
+                .tuning
+                .highlight_height(game.game.piece_at(pos).map(|piece| piece.piece_type))
+        });
+        commands.spawn((
+            SceneRoot(handle.clone()),
+            PossibleMoveHighlight {
+                base_height,
+                individual_offset: (pos.x + pos.y + 1) as f32 / 15.,
+            },
+            ChildOf(square),
+        ));
+    }
+}
+
+/// Resets the game, which puts the models of all pieces back onto their initial squares.
+fn new_game_handler(_: On<NewGameEvent>, mut commands: Commands, mut game: ResMut<ChessGame>) {
+    info!("Starting a new game");
+    *game = ChessGame::default();
+    commands.trigger(GameReplacedEvent {});
+}
+
+/// Replaces the models of all pieces by the ones of the current game.
+fn game_replaced_handler(
+    _: On<GameReplacedEvent>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game: Res<ChessGame>,
+    asset_packs: Res<AssetPacks>,
+    layout: Res<BoardLayout>,
+    pieces: Query<Entity, With<PieceMarker>>,
+) {
+    for entity in &pieces {
+        commands.entity(entity).despawn();
+    }
+    spawn_pieces(
+        &mut commands,
+        &asset_server,
+        &game.game,
+        &asset_packs,
+        &layout,
+    );
+    commands.trigger(SelectionChangedEvent {});
+}
@@ -0,0 +1,285 @@
+//! HTTP/JSON interface to the game logic, started with `--serve [address]`.
+//!
+//! Routes:
+//! - `POST /games`: creates a game, returns its id
+//! - `GET /games/{id}`: board, active color, check, status (`playing`, `checkmate`, `stalemate`
+//!   or `draw` if neither side can checkmate) and winner
+//! - `GET /games/{id}/moves`: valid moves of the active color, e.g. `"e2e4"`
+//! - `POST /games/{id}/moves` with body `{"move": "e2e4"}`: performs the move, returns the new
+//!   state
+
+use std::collections::HashMap;
+
+use chess::gamelogic::{
+    coordinates::Position,
+    game::Game,
+    moves::{Move, MoveRequest},
+    pieces::Color,
+};
+use serde_json::{Value, json};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:8080";
+
+/// The games created through the API, by id.
+#[derive(Default)]
+struct Games {
+    games: HashMap<u64, Game>,
+    next_id: u64,
+}
+
+/// Serves requests until the process is stopped. If `verbose`, every request is printed with the
+/// status of its response.
+pub fn serve(address: &str, verbose: bool) {
+    let server = Server::http(address).expect("could not bind server address");
+    println!("Serving chess API on http://{}", address);
+
+    let mut games = Games::default();
+    for mut request in server.incoming_requests() {
+        let mut body = String::new();
+        let _ = request.as_reader().read_to_string(&mut body);
+        let (status, response) = handle(request.method(), request.url(), &body, &mut games);
+        if verbose {
+            println!("{} {} -> {}", request.method(), request.url(), status);
+        }
+        respond(request, status, response);
+    }
+}
+
+/// Status and JSON body of the response to a request.
+fn handle(method: &Method, url: &str, body: &str, games: &mut Games) -> (u16, Value) {
+    let segments: Vec<&str> = url
+        .trim_matches('/')
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect();
+
+    match (method, segments.as_slice()) {
+        (Method::Post, ["games"]) => {
+            let id = games.next_id;
+            games.games.insert(id, Game::new());
+            games.next_id += 1;
+            (201, json!({ "id": id }))
+        }
+        (Method::Get, ["games", id]) => match game_by_id(&games.games, id) {
+            Some(game) => (200, game_state(game)),
+            None => not_found(),
+        },
+        (Method::Get, ["games", id, "moves"]) => match game_by_id(&games.games, id) {
+            Some(game) => {
+                let moves: Vec<String> = game.legal_moves().iter().map(Move::uci).collect();
+                (200, json!({ "moves": moves }))
+            }
+            None => not_found(),
+        },
+        (Method::Post, ["games", id, "moves"]) => {
+            let Some(game) = id.parse().ok().and_then(|id| games.games.get_mut(&id)) else {
+                return not_found();
+            };
+            let move_req = serde_json::from_str::<Value>(body)
+                .ok()
+                .and_then(|body| body["move"].as_str().map(str::to_owned))
+                .and_then(|name| MoveRequest::parse(&name, game.active_color()));
+            match move_req.and_then(|req| game.perform_move_request(req)) {
+                Some(next) => {
+                    *game = next;
+                    (200, game_state(game))
+                }
+                None => (400, json!({ "error": "invalid move" })),
+            }
+        }
+        _ => not_found(),
+    }
+}
+
+fn respond(request: Request, status: u16, body: Value) {
+    let content_type = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap();
+    let response = Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(content_type);
+    if let Err(err) = request.respond(response) {
+        eprintln!("Could not send response: {}", err);
+    }
+}
+
+fn not_found() -> (u16, Value) {
+    (404, json!({ "error": "not found" }))
+}
+
+fn game_by_id<'a>(games: &'a HashMap<u64, Game>, id: &str) -> Option<&'a Game> {
+    id.parse().ok().and_then(|id| games.get(&id))
+}
+
+fn game_state(game: &Game) -> Value {
+    let mut board: Vec<_> = game.pieces().collect();
+    board.sort_by_key(|(pos, _)| (pos.x, pos.y));
+    let pieces: Vec<Value> = board
+        .into_iter()
+        .map(|(pos, piece)| {
+            json!({
+                "square": square_name(pos),
+                "type": format!("{:?}", piece.piece_type).to_lowercase(),
+                "color": color_name(piece.color),
+            })
+        })
+        .collect();
+    let active = game.active_color();
+    json!({
+        "active_color": color_name(active),
+        "in_check": game.is_king_in_check(active),
+        "status": status(game),
+        "winner": game.winner().map(color_name),
+        "pieces": pieces,
+    })
+}
+
+fn status(game: &Game) -> &'static str {
+    if game.is_checkmate() {
+        "checkmate"
+    } else if game.is_stalemate() {
+        "stalemate"
+    } else if !game.has_mating_material(Color::White) && !game.has_mating_material(Color::Black) {
+        "draw"
+    } else {
+        "playing"
+    }
+}
+
+fn color_name(color: Color) -> &'static str {
+    match color {
+        Color::White => "white",
+        Color::Black => "black",
+    }
+}
+
+fn square_name(pos: Position) -> String {
+    pos.to_string().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chess::gamelogic::testing;
+
+    fn created(games: &mut Games) -> String {
+        let (status, body) = handle(&Method::Post, "/games", "", games);
+        assert_eq!(status, 201);
+        body["id"].to_string()
+    }
+
+    #[test]
+    fn games_get_increasing_ids() {
+        let mut games = Games::default();
+        assert_eq!(created(&mut games), "0");
+        assert_eq!(created(&mut games), "1");
+    }
+
+    #[test]
+    fn state_and_moves_of_new_game() {
+        let mut games = Games::default();
+        let id = created(&mut games);
+
+        let (status, state) = handle(&Method::Get, &format!("/games/{id}"), "", &mut games);
+        assert_eq!(status, 200);
+        assert_eq!(state["active_color"], "white");
+        assert_eq!(state["in_check"], false);
+        assert_eq!(state["status"], "playing");
+        assert_eq!(state["winner"], Value::Null);
+        assert_eq!(state["pieces"].as_array().unwrap().len(), 32);
+        assert_eq!(
+            state["pieces"][0],
+            json!({ "square": "a1", "type": "rook", "color": "white" })
+        );
+
+        let (status, moves) = handle(&Method::Get, &format!("/games/{id}/moves"), "", &mut games);
+        assert_eq!(status, 200);
+        let moves = moves["moves"].as_array().unwrap();
+        assert_eq!(moves.len(), 20);
+        assert!(moves.contains(&json!("e2e4")));
+    }
+
+    #[test]
+    fn moves_are_performed() {
+        let mut games = Games::default();
+        let id = created(&mut games);
+        let url = format!("/games/{id}/moves");
+
+        let (status, state) = handle(&Method::Post, &url, r#"{"move": "e2e4"}"#, &mut games);
+        assert_eq!(status, 200);
+        assert_eq!(state["active_color"], "black");
+        let (_, state) = handle(&Method::Get, &format!("/games/{id}"), "", &mut games);
+        assert_eq!(state["active_color"], "black");
+    }
+
+    #[test]
+    fn checkmate_has_a_winner() {
+        let mut games = Games::default();
+        let id = created(&mut games);
+        let url = format!("/games/{id}/moves");
+        for name in ["f2f3", "e7e5", "g2g4", "d8h4"] {
+            let body = json!({ "move": name }).to_string();
+            assert_eq!(handle(&Method::Post, &url, &body, &mut games).0, 200);
+        }
+
+        let (_, state) = handle(&Method::Get, &format!("/games/{id}"), "", &mut games);
+        assert_eq!(state["in_check"], true);
+        assert_eq!(state["status"], "checkmate");
+        assert_eq!(state["winner"], "black");
+    }
+
+    #[test]
+    fn stalemate_and_dead_positions_are_drawn() {
+        let mut games = Games::default();
+        games.games.insert(0, testing::stalemate());
+        games.games.insert(1, testing::insufficient_material());
+        for (id, status) in [(0, "stalemate"), (1, "draw")] {
+            let (_, state) = handle(&Method::Get, &format!("/games/{id}"), "", &mut games);
+            assert_eq!(state["status"], status);
+            assert_eq!(state["winner"], Value::Null);
+        }
+    }
+
+    #[test]
+    fn unknown_ids_and_routes_are_not_found() {
+        let mut games = Games::default();
+        created(&mut games);
+        let move_body = r#"{"move": "e2e4"}"#;
+        for (method, url, body) in [
+            (Method::Get, "/games/1", ""),
+            (Method::Get, "/games/white", ""),
+            (Method::Get, "/games/1/moves", ""),
+            (Method::Post, "/games/1/moves", move_body),
+            (Method::Post, "/games/-1/moves", move_body),
+            (Method::Get, "/games", ""),
+            (Method::Delete, "/games/0", ""),
+            (Method::Get, "/", ""),
+        ] {
+            let (status, body) = handle(&method, url, body, &mut games);
+            assert_eq!(status, 404, "{} {}", method, url);
+            assert_eq!(body, json!({ "error": "not found" }));
+        }
+    }
+
+    #[test]
+    fn malformed_and_invalid_moves_are_rejected() {
+        let mut games = Games::default();
+        let id = created(&mut games);
+        let url = format!("/games/{id}/moves");
+        for body in [
+            "",
+            "e2e4",
+            r#"{"move": "e2e4""#,
+            r#"{"mov": "e2e4"}"#,
+            r#"{"move": 12}"#,
+            r#"{"move": "e2"}"#,
+            r#"{"move": "e2e5"}"#,
+            r#"{"move": "e7e5"}"#,
+        ] {
+            let (status, response) = handle(&Method::Post, &url, body, &mut games);
+            assert_eq!(status, 400, "{}", body);
+            assert_eq!(response, json!({ "error": "invalid move" }));
+        }
+        let (_, state) = handle(&Method::Get, &format!("/games/{id}"), "", &mut games);
+        assert_eq!(state["active_color"], "white");
+    }
+}
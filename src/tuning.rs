@@ -0,0 +1,130 @@
+use bevy::prelude::*;
+use std::time::SystemTime;
+
+use crate::gamelogic::pieces::PieceType;
+
+/// The tuning file, relative to the crate root. Its content at build time is compiled in.
+const TUNING_FILE: &str = "assets/tuning.txt";
+
+/// Animation timings and highlight offsets, read from `assets/tuning.txt`.
+///
+/// Every line of the file is a `<name> <value>` pair, e.g. `move_speed 15`. Malformed lines, like
+/// comments starting with `#`, are skipped, and values missing in the file keep their built-in
+/// default. Debug builds read the file again whenever it changes, so values can be tuned while
+/// the game runs.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct Tuning {
+    /// Speed at which moved pieces slide to their new square, in world units per second
+    pub move_speed: f32,
+    /// Height by which the selected piece is lifted, relative to the model scale
+    pub lift_height: f32,
+    /// Duration of the transition between two camera presets, in seconds
+    pub camera_transition: f32,
+    /// Height of the floating highlight above an empty square
+    pub highlight_empty: f32,
+    /// Distance of the floating highlight above the top of a piece to capture
+    pub highlight_gap: f32,
+    /// Height of the floating highlight above a piece to capture whose model was not measured
+    /// yet, per piece type
+    pub highlight_pawn: f32,
+    pub highlight_knight: f32,
+    pub highlight_bishop: f32,
+    pub highlight_rook: f32,
+    pub highlight_queen: f32,
+    pub highlight_king: f32,
+}
+
+impl Default for Tuning {
+    fn default() -> Self {
+        Self::BUILT_IN.updated(include_str!("../assets/tuning.txt"))
+    }
+}
+
+impl Tuning {
+    /// Values used for names missing in the tuning file.
+    pub const BUILT_IN: Self = Self {
+        move_speed: 15.,
+        lift_height: 0.6,
+        camera_transition: 1.2,
+        highlight_empty: 0.2,
+        highlight_gap: 0.3,
+        highlight_pawn: 2.6,
+        highlight_knight: 2.8,
+        highlight_bishop: 3.5,
+        highlight_rook: 2.5,
+        highlight_queen: 3.8,
+        highlight_king: 4.1,
+    };
+
+    /// These values, with the ones given in `text` in the format of the tuning file replacing them.
+    pub fn updated(mut self, text: &str) -> Self {
+        for line in text.lines() {
+            let Some((name, value)) = line.trim().split_once(char::is_whitespace) else {
+                continue;
+            };
+            let Ok(value) = value.trim().parse::<f32>() else {
+                continue;
+            };
+            let field = match name {
+                "move_speed" => &mut self.move_speed,
+                "lift_height" => &mut self.lift_height,
+                "camera_transition" => &mut self.camera_transition,
+                "highlight_empty" => &mut self.highlight_empty,
+                "highlight_gap" => &mut self.highlight_gap,
+                "highlight_pawn" => &mut self.highlight_pawn,
+                "highlight_knight" => &mut self.highlight_knight,
+                "highlight_bishop" => &mut self.highlight_bishop,
+                "highlight_rook" => &mut self.highlight_rook,
+                "highlight_queen" => &mut self.highlight_queen,
+                "highlight_king" => &mut self.highlight_king,
+                _ => continue,
+            };
+            *field = value;
+        }
+        self
+    }
+
+    /// Height of the floating highlight above a square with a piece of `piece_type`, or above an
+    /// empty square if None.
+    pub fn highlight_height(&self, piece_type: Option<PieceType>) -> f32 {
+        match piece_type {
+            Some(PieceType::Pawn) => self.highlight_pawn,
+            Some(PieceType::Knight) => self.highlight_knight,
+            Some(PieceType::Bishop) => self.highlight_bishop,
+            Some(PieceType::Rook) => self.highlight_rook,
+            Some(PieceType::Queen) => self.highlight_queen,
+            Some(PieceType::King) => self.highlight_king,
+            None => self.highlight_empty,
+        }
+    }
+}
+
+/// Reads the tuning file again when it was modified since the last check, at most once a second.
+pub fn reload_tuning(
+    mut tuning: ResMut<Tuning>,
+    mut last_modified: Local<Option<SystemTime>>,
+    mut timer: Local<Timer>,
+    time: Res<Time<Real>>,
+) {
+    if timer.duration().is_zero() {
+        *timer = Timer::from_seconds(1., TimerMode::Repeating);
+    }
+    if !timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(TUNING_FILE);
+    let Ok(modified) = std::fs::metadata(&path).and_then(|metadata| metadata.modified()) else {
+        return;
+    };
+    if *last_modified == Some(modified) {
+        return;
+    }
+    *last_modified = Some(modified);
+    if let Ok(text) = std::fs::read_to_string(&path) {
+        let reloaded = Tuning::BUILT_IN.updated(&text);
+        if reloaded != *tuning {
+            *tuning = reloaded;
+            info!("Reloaded {}", path.display());
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use bevy::{
+    diagnostic::{EntityCountDiagnosticsPlugin, FrameTimeDiagnosticsPlugin},
+    input::InputSystems,
+    prelude::*,
+};
+
+use crate::{console, diagnostics, draw_claim, input, log_buffer, mate_solver, move_hint, pause};
+
+/// The pause menu, the pause state of the game, the draw claim button and result, the hints
+/// (explanations of illegal moves, mate search), the developer console and the diagnostics
+/// overlay.
+pub struct UiPlugin;
+
+impl Plugin for UiPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<pause::GameState>()
+            .add_systems(
+                Update,
+                // Esc only pauses without selection, so this has to run before it is cleared
+                pause::toggle_pause.before(input::cancel_selection),
+            )
+            .add_systems(
+                Update,
+                pause::pause_menu_buttons.run_if(in_state(pause::GameState::Paused)),
+            )
+            .add_systems(OnEnter(pause::GameState::Paused), pause::enter_pause)
+            .add_systems(OnExit(pause::GameState::Paused), pause::exit_pause)
+            .add_systems(
+                Update,
+                (
+                    draw_claim::update_claim_draw_button,
+                    draw_claim::claim_draw.run_if(in_state(pause::GameState::Playing)),
+                    draw_claim::start_game_after_draw.run_if(in_state(pause::GameState::Playing)),
+                ),
+            )
+            .add_observer(draw_claim::remove_draw_result)
+            .add_systems(Update, move_hint::expire_move_hints)
+            .add_systems(
+                Update,
+                mate_solver::show_mating_moves.run_if(in_state(pause::GameState::Playing)),
+            )
+            .add_observer(move_hint::show_move_hint)
+            .init_resource::<console::Console>()
+            .init_resource::<log_buffer::LogBuffer>()
+            .add_systems(PreUpdate, console::console_input.after(InputSystems))
+            .add_systems(Update, console::update_console_text)
+            .add_observer(console::run_console_command)
+            .add_plugins((
+                FrameTimeDiagnosticsPlugin::default(),
+                EntityCountDiagnosticsPlugin::default(),
+            ))
+            .add_systems(
+                Update,
+                (
+                    diagnostics::toggle_diagnostics_overlay,
+                    diagnostics::update_diagnostics_overlay,
+                )
+                    .chain(),
+            );
+    }
+}
@@ -0,0 +1,62 @@
+use chess::{
+    gamelogic::{
+        coordinates::Position,
+        game::Game,
+        moves::MoveRequest,
+        pieces::{Color::*, Piece, PieceType::*},
+        testing::GameBuilder,
+    },
+    highlights::MoveKind,
+};
+
+fn kind(game: &Game, origin: &str, destination: &str, assist: bool) -> MoveKind {
+    let mov = MoveRequest::new(
+        Position::from_str(origin),
+        Position::from_str(destination),
+        None,
+    )
+    .check(game)
+    .unwrap();
+    MoveKind::of(mov, game, assist)
+}
+
+fn position() -> Game {
+    GameBuilder::empty()
+        .with(Piece::new(King, White), "a1")
+        .with(Piece::new(Rook, White), "d1")
+        .with(Piece::new(King, Black), "h8")
+        .with(Piece::new(Knight, Black), "d5")
+        .with(Piece::new(Pawn, Black), "e6")
+        .with(Piece::new(Knight, Black), "b5")
+        .build()
+}
+
+#[test]
+fn without_assist_only_captures_are_distinguished() {
+    let game = position();
+    assert_eq!(kind(&game, "D1", "D5", false), MoveKind::Capture);
+    assert_eq!(kind(&game, "D1", "D4", false), MoveKind::Quiet);
+    assert_eq!(kind(&game, "D1", "H1", false), MoveKind::Quiet);
+}
+
+#[test]
+fn assist_shows_checks_and_unsafe_squares() {
+    let game = position();
+    assert_eq!(kind(&game, "D1", "H1", true), MoveKind::Check);
+    // the knight on D5 is defended by the pawn on E6
+    assert_eq!(kind(&game, "D1", "D5", true), MoveKind::Unsafe);
+    // attacked by the knight on B5
+    assert_eq!(kind(&game, "D1", "D4", true), MoveKind::Unsafe);
+    assert_eq!(kind(&game, "D1", "D3", true), MoveKind::Quiet);
+}
+
+#[test]
+fn undefended_capture_is_a_capture() {
+    let game = GameBuilder::empty()
+        .with(Piece::new(King, White), "a1")
+        .with(Piece::new(Rook, White), "d1")
+        .with(Piece::new(King, Black), "h8")
+        .with(Piece::new(Knight, Black), "d5")
+        .build();
+    assert_eq!(kind(&game, "D1", "D5", true), MoveKind::Capture);
+}
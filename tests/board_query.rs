@@ -0,0 +1,33 @@
+use chess::gamelogic::{
+    game::Game,
+    pieces::{Color::*, Piece, PieceType::*},
+    testing::{GameBuilder, square_at},
+};
+
+#[test]
+fn initial_position_has_sixteen_pieces_per_side() {
+    let game = Game::new();
+    assert_eq!(game.pieces().count(), 32);
+    assert_eq!(game.pieces_of(White).count(), 16);
+    assert!(game.pieces_of(Black).all(|(pos, _)| pos.y >= 6));
+}
+
+#[test]
+fn find_returns_all_matching_pieces() {
+    let game = GameBuilder::empty()
+        .with(Piece::new(King, White), "e1")
+        .with(Piece::new(Knight, White), "b1")
+        .with(Piece::new(Knight, White), "g1")
+        .with(Piece::new(Knight, Black), "b8")
+        .with(Piece::new(King, Black), "e8")
+        .build();
+
+    let mut knights: Vec<_> = game.find(Knight, White).collect();
+    knights.sort_by_key(|pos| pos.x);
+    assert_eq!(knights, vec![square_at("b1"), square_at("g1")]);
+    assert_eq!(
+        game.find(King, Black).collect::<Vec<_>>(),
+        vec![square_at("e8")]
+    );
+    assert_eq!(game.find(Queen, White).next(), None);
+}
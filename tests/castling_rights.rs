@@ -0,0 +1,75 @@
+use chess::gamelogic::{
+    game::{CastlingRights, Game},
+    moves::{MoveError, MoveRequest},
+    pieces::{Color::*, Piece, PieceType::*},
+    testing::{GameBuilder, play, square_at},
+};
+
+fn castle(game: &Game, origin: &str, destination: &str) -> Result<(), MoveError> {
+    MoveRequest::new(square_at(origin), square_at(destination), None)
+        .check(game)
+        .map(|_| ())
+}
+
+fn kings_and_rooks() -> GameBuilder {
+    GameBuilder::empty()
+        .with(Piece::new(King, White), "e1")
+        .with(Piece::new(Rook, White), "a1")
+        .with(Piece::new(Rook, White), "h1")
+        .with(Piece::new(King, Black), "e8")
+        .with(Piece::new(Rook, Black), "a8")
+        .with(Piece::new(Rook, Black), "h8")
+}
+
+#[test]
+fn initial_position_has_all_rights() {
+    assert_eq!(Game::new().castling_rights(), CastlingRights::ALL);
+}
+
+#[test]
+fn king_and_rook_moves_clear_rights() {
+    let game = play(&kings_and_rooks().build(), &[("h1", "h2"), ("e8", "d8")]);
+    assert_eq!(
+        game.castling_rights(),
+        CastlingRights {
+            white_kingside: false,
+            white_queenside: true,
+            black_kingside: false,
+            black_queenside: false,
+        }
+    );
+}
+
+#[test]
+fn rook_capture_clears_right_of_captured_rook() {
+    let game = play(&kings_and_rooks().build(), &[("a1", "a8")]);
+    assert_eq!(
+        game.castling_rights(),
+        CastlingRights {
+            white_kingside: true,
+            white_queenside: false,
+            black_kingside: true,
+            black_queenside: false,
+        }
+    );
+}
+
+#[test]
+fn explicit_rights_override_moved_pieces() {
+    let none = kings_and_rooks()
+        .castling_rights(CastlingRights::NONE)
+        .build();
+    assert_eq!(castle(&none, "e1", "g1"), Err(MoveError::KingMoved));
+
+    let moved_king = GameBuilder::empty()
+        .with_moved(Piece::new(King, White), "e1")
+        .with_moved(Piece::new(Rook, White), "h1")
+        .with(Piece::new(King, Black), "e8")
+        .castling_rights(CastlingRights {
+            white_kingside: true,
+            ..CastlingRights::NONE
+        })
+        .build();
+    assert_eq!(castle(&moved_king, "e1", "g1"), Ok(()));
+    assert_eq!(castle(&moved_king, "e1", "c1"), Err(MoveError::RookMoved));
+}
@@ -0,0 +1,47 @@
+use chess::gamelogic::{
+    game::Game,
+    pieces::{Color::*, Piece, PieceType::*},
+    testing::{GameBuilder, square_at},
+};
+
+#[test]
+fn no_checkers_without_check() {
+    let game = Game::new();
+    assert!(game.checkers(White).is_empty());
+    assert!(!game.is_king_in_check(White));
+}
+
+#[test]
+fn finds_pawn_and_knight_checks() {
+    let pawn = GameBuilder::empty()
+        .with(Piece::new(King, White), "e4")
+        .with(Piece::new(Pawn, Black), "d5")
+        .with(Piece::new(Pawn, White), "f5")
+        .with(Piece::new(King, Black), "h8")
+        .build();
+    assert_eq!(pawn.checkers(White), vec![square_at("d5")]);
+
+    let knight = GameBuilder::empty()
+        .with(Piece::new(King, White), "e1")
+        .with(Piece::new(Knight, Black), "f3")
+        .with(Piece::new(King, Black), "h8")
+        .build();
+    assert_eq!(knight.checkers(White), vec![square_at("f3")]);
+}
+
+#[test]
+fn finds_both_pieces_of_double_check() {
+    let game = GameBuilder::empty()
+        .with(Piece::new(King, Black), "e8")
+        .with(Piece::new(Rook, White), "e1")
+        .with(Piece::new(Bishop, White), "b5")
+        // blocked by the own knight
+        .with(Piece::new(Queen, White), "h8")
+        .with(Piece::new(Knight, Black), "g8")
+        .with(Piece::new(King, White), "a1")
+        .to_move(Black)
+        .build();
+    let mut checkers = game.checkers(Black);
+    checkers.sort_by_key(|pos| pos.x);
+    assert_eq!(checkers, vec![square_at("b5"), square_at("e1")]);
+}
@@ -0,0 +1,36 @@
+use chess::gamelogic::{
+    game::Game,
+    pieces::{Color::*, Piece, PieceType::*},
+    testing::GameBuilder,
+};
+
+#[test]
+fn prints_initial_position() {
+    assert_eq!(
+        Game::new().to_string(),
+        "\
+8 ♜ ♞ ♝ ♛ ♚ ♝ ♞ ♜
+7 ♟ ♟ ♟ ♟ ♟ ♟ ♟ ♟
+6 · · · · · · · ·
+5 · · · · · · · ·
+4 · · · · · · · ·
+3 · · · · · · · ·
+2 ♙ ♙ ♙ ♙ ♙ ♙ ♙ ♙
+1 ♖ ♘ ♗ ♕ ♔ ♗ ♘ ♖
+  a b c d e f g h
+White to move"
+    );
+}
+
+#[test]
+fn prints_side_to_move() {
+    let game = GameBuilder::empty()
+        .with(Piece::new(King, White), "a1")
+        .with(Piece::new(King, Black), "h8")
+        .to_move(Black)
+        .build();
+    let text = game.to_string();
+    assert!(text.starts_with("8 · · · · · · · ♚\n"));
+    assert!(text.contains("\n1 ♔ · · · · · · ·\n"));
+    assert!(text.ends_with("Black to move"));
+}
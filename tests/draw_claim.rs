@@ -0,0 +1,86 @@
+use chess::gamelogic::{
+    game::Game,
+    moves::Move,
+    pieces::{Color::*, Piece, PieceType::*},
+    testing::{GameBuilder, play},
+};
+
+const KNIGHT_SHUFFLE: [(&str, &str); 4] = [("G1", "F3"), ("G8", "F6"), ("F3", "G1"), ("F6", "G8")];
+
+#[test]
+fn threefold_repetition_can_be_claimed() {
+    let game = play(&Game::new(), &KNIGHT_SHUFFLE);
+    assert_eq!(game.repetitions(), 2);
+    assert!(!game.can_claim_draw());
+
+    let game = play(&game, &KNIGHT_SHUFFLE);
+    assert_eq!(game.repetitions(), 3);
+    assert!(game.can_claim_draw());
+}
+
+#[test]
+fn pawn_move_resets_repetitions_and_clock() {
+    let game = play(&Game::new(), &KNIGHT_SHUFFLE);
+    assert_eq!(game.halfmove_clock(), 4);
+
+    let game = play(&game, &[("E2", "E4")]);
+    assert_eq!(game.halfmove_clock(), 0);
+    assert_eq!(game.repetitions(), 1);
+}
+
+#[test]
+fn capture_resets_clock() {
+    let game = play(
+        &Game::new(),
+        &[("E2", "E4"), ("D7", "D5"), ("G1", "F3"), ("D8", "D6")],
+    );
+    assert_eq!(game.halfmove_clock(), 2);
+    let game = play(&game, &[("E4", "D5")]);
+    assert_eq!(game.halfmove_clock(), 0);
+}
+
+#[test]
+fn lost_castling_rights_make_a_different_position() {
+    let start = GameBuilder::empty()
+        .with(Piece::new(King, White), "e1")
+        .with(Piece::new(Rook, White), "h1")
+        .with(Piece::new(King, Black), "e8")
+        .with(Piece::new(Rook, Black), "h8")
+        .build();
+    let rook_shuffle = [("H1", "H2"), ("H8", "H7"), ("H2", "H1"), ("H7", "H8")];
+
+    // the pieces are back on their squares, but the rooks have lost the right to castle
+    let game = play(&start, &rook_shuffle);
+    assert_eq!(game.repetitions(), 1);
+
+    let game = play(&game, &rook_shuffle);
+    assert_eq!(game.repetitions(), 2);
+}
+
+#[test]
+fn fifty_move_rule_can_be_claimed() {
+    let mut game = GameBuilder::empty()
+        .with(Piece::new(King, White), "a1")
+        .with(Piece::new(King, Black), "h8")
+        .with(Piece::new(Rook, White), "c3")
+        .with(Piece::new(Rook, Black), "f6")
+        .build();
+    // shuffle without captures and without repeating a position
+    for _ in 0..100 {
+        assert!(!game.can_claim_draw());
+        // in a fixed order, as the order of the legal moves is not deterministic
+        let mut moves = game.legal_moves();
+        moves.sort_by_key(|mov| {
+            let (origin, destination) = (mov.origin(), mov.destination());
+            (origin.x, origin.y, destination.x, destination.y)
+        });
+        game = moves
+            .into_iter()
+            .filter(|mov| !matches!(mov, Move::NormalMove(normal_move) if normal_move.throwing.is_some()))
+            .filter_map(|mov| game.perform_move(mov))
+            .find(|next| next.repetitions() == 1)
+            .expect("no new position reachable");
+    }
+    assert_eq!(game.halfmove_clock(), 100);
+    assert!(game.can_claim_draw());
+}
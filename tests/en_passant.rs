@@ -0,0 +1,44 @@
+use chess::gamelogic::{
+    game::Game,
+    moves::MoveRequest,
+    testing::{play, square_at},
+};
+
+#[test]
+fn target_is_the_square_passed_over() {
+    assert_eq!(Game::new().en_passant_target(), None);
+    assert_eq!(
+        play(&Game::new(), &[("e2", "e4")]).en_passant_target(),
+        Some(square_at("e3"))
+    );
+    assert_eq!(
+        play(&Game::new(), &[("e2", "e4"), ("d7", "d5")]).en_passant_target(),
+        Some(square_at("d6"))
+    );
+}
+
+#[test]
+fn target_is_cleared_by_other_moves() {
+    assert_eq!(
+        play(&Game::new(), &[("e2", "e3")]).en_passant_target(),
+        None
+    );
+    assert_eq!(
+        play(&Game::new(), &[("e2", "e4"), ("g8", "f6")]).en_passant_target(),
+        None
+    );
+}
+
+#[test]
+fn capture_moves_to_the_target() {
+    let game = play(
+        &Game::new(),
+        &[("e2", "e4"), ("a7", "a6"), ("e4", "e5"), ("d7", "d5")],
+    );
+    let target = game.en_passant_target().unwrap();
+    let after = game
+        .perform_move_request(MoveRequest::new(square_at("e5"), target, None))
+        .unwrap();
+    assert!(after.piece_at(square_at("d5")).is_none());
+    assert!(after.piece_at(square_at("d6")).is_some());
+}
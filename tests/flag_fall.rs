@@ -0,0 +1,62 @@
+use chess::gamelogic::{
+    pieces::{Color::*, Piece, PieceType::*},
+    testing::{self, GameBuilder},
+};
+
+#[test]
+fn opponent_wins_with_mating_material() {
+    let game = GameBuilder::kings()
+        .with(Piece::new(Rook, Black), "a8")
+        .build();
+    assert_eq!(game.flag_fall_winner(White), Some(Black));
+}
+
+#[test]
+fn opponent_with_pawn_wins() {
+    let game = GameBuilder::kings()
+        .with(Piece::new(Pawn, Black), "a7")
+        .build();
+    assert_eq!(game.flag_fall_winner(White), Some(Black));
+}
+
+#[test]
+fn draw_against_bare_king() {
+    let game = GameBuilder::kings()
+        .with(Piece::new(Pawn, White), "a2")
+        .build();
+    assert_eq!(game.flag_fall_winner(White), None);
+}
+
+#[test]
+fn draw_against_lone_minor_piece() {
+    let knight = GameBuilder::kings()
+        .with(Piece::new(Knight, Black), "b8")
+        .build();
+    assert_eq!(knight.flag_fall_winner(White), None);
+    let bishop = GameBuilder::kings()
+        .with(Piece::new(Bishop, Black), "c8")
+        .build();
+    assert_eq!(bishop.flag_fall_winner(White), None);
+}
+
+#[test]
+fn lone_knight_wins_if_mate_is_possible() {
+    let game = GameBuilder::kings()
+        .with(Piece::new(Pawn, White), "a2")
+        .with(Piece::new(Knight, Black), "b8")
+        .build();
+    assert_eq!(game.flag_fall_winner(White), Some(Black));
+}
+
+#[test]
+fn bishops_on_same_square_color() {
+    let same_color = testing::insufficient_material();
+    assert_eq!(same_color.flag_fall_winner(White), None);
+    assert_eq!(same_color.flag_fall_winner(Black), None);
+
+    let other_color = GameBuilder::kings()
+        .with(Piece::new(Bishop, White), "c1")
+        .with(Piece::new(Bishop, Black), "c8")
+        .build();
+    assert_eq!(other_color.flag_fall_winner(White), Some(Black));
+}
@@ -0,0 +1,123 @@
+use chess::gamelogic::{
+    game::Game,
+    moves::MoveRequest,
+    pieces::Color::*,
+    testing::square_at,
+    tree::{GameTree, NodeId},
+};
+
+fn request(name: &str) -> MoveRequest {
+    MoveRequest::new(square_at(&name[0..2]), square_at(&name[2..4]), None)
+}
+
+/// Plays `moves` one after the other from `from`, returns the last node.
+fn line(tree: &mut GameTree, from: NodeId, moves: &[&str]) -> NodeId {
+    moves.iter().fold(from, |node, name| {
+        tree.add_move(node, request(name))
+            .unwrap_or_else(|| panic!("{name} is not valid"))
+    })
+}
+
+fn uci(tree: &GameTree, nodes: &[NodeId]) -> Vec<String> {
+    nodes
+        .iter()
+        .filter_map(|&node| tree.node(node).mov())
+        .map(|mov| mov.uci())
+        .collect()
+}
+
+#[test]
+fn adding_existing_move_returns_its_node() {
+    let mut tree = GameTree::new(Game::new());
+    let root = tree.root();
+    let e4 = line(&mut tree, root, &["e2e4"]);
+    assert_eq!(tree.add_move(root, request("e2e4")), Some(e4));
+    assert_eq!(tree.node(root).children(), [e4]);
+    assert_eq!(tree.node(e4).game().active_color(), Black);
+}
+
+#[test]
+fn invalid_move_is_not_added() {
+    let mut tree = GameTree::new(Game::new());
+    let root = tree.root();
+    assert_eq!(tree.add_move(root, request("e2e5")), None);
+    // a valid move of the side not to move
+    assert_eq!(tree.add_move(root, request("e7e5")), None);
+    assert_eq!(tree.add_move(root, request("e3e4")), None);
+    assert!(tree.node(root).children().is_empty());
+}
+
+#[test]
+fn path_and_main_line() {
+    let mut tree = GameTree::new(Game::new());
+    let root = tree.root();
+    let e5 = line(&mut tree, root, &["e2e4", "e7e5"]);
+    let nf3 = line(&mut tree, e5, &["g1f3", "b8c6"]);
+    let e4 = tree.node(e5).parent().unwrap();
+    let c5 = line(&mut tree, e4, &["c7c5"]);
+
+    assert_eq!(
+        uci(&tree, &tree.path(nf3)),
+        ["e2e4", "e7e5", "g1f3", "b8c6"]
+    );
+    assert_eq!(uci(&tree, &tree.path(c5)), ["e2e4", "c7c5"]);
+    assert_eq!(tree.path(root), vec![root]);
+    assert_eq!(
+        uci(&tree, &tree.main_line(root)),
+        ["e2e4", "e7e5", "g1f3", "b8c6"]
+    );
+    assert_eq!(tree.main_line(c5), vec![c5]);
+}
+
+#[test]
+fn promote_makes_variation_main_line_up_to_root() {
+    let mut tree = GameTree::new(Game::new());
+    let root = tree.root();
+    line(&mut tree, root, &["e2e4", "e7e5"]);
+    let d4 = line(&mut tree, root, &["d2d4"]);
+    line(&mut tree, d4, &["d7d5"]);
+    line(&mut tree, d4, &["g8f6"]);
+    let e6 = line(&mut tree, d4, &["e7e6"]);
+
+    tree.promote(e6);
+    assert_eq!(uci(&tree, &tree.main_line(root)), ["d2d4", "e7e6"]);
+    // the former main lines stay in order behind the promoted ones
+    assert_eq!(
+        uci(&tree, tree.node(d4).children()),
+        ["e7e6", "d7d5", "g8f6"]
+    );
+    assert_eq!(uci(&tree, tree.node(root).children()), ["d2d4", "e2e4"]);
+}
+
+#[test]
+fn remove_detaches_subtree_and_keeps_other_ids() {
+    let mut tree = GameTree::new(Game::new());
+    let root = tree.root();
+    let e4 = line(&mut tree, root, &["e2e4"]);
+    let e5 = line(&mut tree, e4, &["e7e5"]);
+    let c5 = line(&mut tree, e4, &["c7c5"]);
+    let nf3 = line(&mut tree, c5, &["g1f3"]);
+    let d4 = line(&mut tree, root, &["d2d4"]);
+
+    tree.remove(c5);
+    assert_eq!(tree.node(e4).children(), [e5]);
+    assert_eq!(uci(&tree, &[e5, d4]), ["e7e5", "d2d4"]);
+    assert_eq!(tree.path(e5), vec![root, e4, e5]);
+    assert!(!tree.main_line(root).contains(&nf3));
+
+    tree.remove(root);
+    assert_eq!(tree.node(root).children(), [e4, d4]);
+}
+
+#[test]
+fn annotations_can_be_changed() {
+    let mut tree = GameTree::new(Game::new());
+    let root = tree.root();
+    let e4 = line(&mut tree, root, &["e2e4"]);
+    let node = tree.node_mut(e4);
+    node.comment = Some("best by test".to_string());
+    node.eval = Some(30);
+
+    assert_eq!(tree.node(e4).comment.as_deref(), Some("best by test"));
+    assert_eq!(tree.node(e4).eval, Some(30));
+}
@@ -0,0 +1,460 @@
+use bevy::{
+    ecs::system::RunSystemOnce,
+    log::{
+        tracing,
+        tracing_subscriber::{Registry, layer::SubscriberExt},
+    },
+    prelude::*,
+};
+use chess::{
+    BoardClickEvent, ChessGame, GamePlugin, GameReplacedEvent, IllegalMoveEvent, PieceMarker,
+    Promoted,
+    asset_packs::AssetPacks,
+    board::{self, BoardLayout, Square},
+    console::{self, Console, ConsoleCommandEvent},
+    draw_claim::{self, DrawResultPanel},
+    gamelogic::{
+        coordinates::Position,
+        game::Game,
+        moves::MoveError,
+        pieces::{Color, Piece, PieceType},
+        testing::GameBuilder,
+    },
+    highlights::{BeginnerAssist, HighlightAssets, HighlightStyle},
+    log_buffer::LogBuffer,
+    render_sync::{self, PossibleMoveHighlight, SelectedMarker},
+    tuning::Tuning,
+};
+
+/// App with the game logic but without window and renderer. Every piece of the game gets a bare
+/// `PieceMarker` entity in place of its model.
+fn app() -> App {
+    app_with(Game::new())
+}
+
+/// Like `app`, but starting from `game`.
+fn app_with(game: Game) -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, GamePlugin));
+    *app.world_mut().resource_mut::<ChessGame>() = ChessGame::new(game);
+    let pieces: Vec<Position> = app
+        .world()
+        .resource::<ChessGame>()
+        .game
+        .pieces()
+        .map(|(pos, _)| pos)
+        .collect();
+    for pos in pieces {
+        app.world_mut().spawn(PieceMarker { pos });
+    }
+    app.update();
+    app
+}
+
+/// Like `app`, but also with the board squares and the observer placing the selection marker and
+/// the move highlights on them. No models are loaded, as there are no loaders for them.
+fn app_with_highlights() -> App {
+    let mut app = app();
+    app.add_plugins(AssetPlugin::default())
+        .init_asset::<Scene>()
+        .init_asset::<Mesh>()
+        .init_asset::<StandardMaterial>()
+        .init_resource::<AssetPacks>()
+        .init_resource::<BoardLayout>()
+        .init_resource::<Tuning>()
+        .init_resource::<HighlightStyle>()
+        .init_resource::<BeginnerAssist>()
+        .init_resource::<HighlightAssets>()
+        .add_observer(render_sync::new_selection_handler);
+    app.world_mut()
+        .run_system_once(
+            |mut commands: Commands,
+             asset_server: Res<AssetServer>,
+             asset_packs: Res<AssetPacks>,
+             layout: Res<BoardLayout>| {
+                board::spawn_board(&mut commands, &asset_server, &asset_packs, &layout)
+            },
+        )
+        .unwrap();
+    app.update();
+    app
+}
+
+fn click(app: &mut App, pos: Option<&str>) {
+    app.world_mut().trigger(BoardClickEvent {
+        board_pos: pos.map(Position::from_str),
+    });
+    app.update();
+}
+
+fn play(app: &mut App, moves: &[(&str, &str)]) {
+    for &(origin, destination) in moves {
+        click(app, Some(origin));
+        click(app, Some(destination));
+    }
+}
+
+fn markers(app: &mut App) -> Vec<Position> {
+    let mut markers: Vec<Position> = app
+        .world_mut()
+        .query::<&PieceMarker>()
+        .iter(app.world())
+        .map(|marker| marker.pos)
+        .collect();
+    markers.sort_by_key(|pos| (pos.x, pos.y));
+    markers
+}
+
+fn has_marker(app: &mut App, pos: &str) -> bool {
+    markers(app).contains(&Position::from_str(pos))
+}
+
+/// Squares with a child entity that has the component `C`.
+fn squares_with<C: Component>(app: &mut App) -> Vec<Position> {
+    let mut query = app.world_mut().query_filtered::<&ChildOf, With<C>>();
+    let world = app.world();
+    let mut squares: Vec<Position> = query
+        .iter(world)
+        .filter_map(|child_of| world.get::<Square>(child_of.parent()))
+        .map(|square| square.pos)
+        .collect();
+    squares.sort_by_key(|pos| (pos.x, pos.y));
+    squares
+}
+
+#[test]
+fn clicking_own_piece_selects_it() {
+    let mut app = app();
+    click(&mut app, Some("E2"));
+
+    let game = app.world().resource::<ChessGame>();
+    assert_eq!(game.selected_tile, Some(Position::from_str("E2")));
+    let mut destinations: Vec<Position> = game
+        .possible_moves
+        .iter()
+        .map(|mov| mov.destination())
+        .collect();
+    destinations.sort_by_key(|pos| pos.y);
+    assert_eq!(
+        destinations,
+        vec![Position::from_str("E3"), Position::from_str("E4")]
+    );
+}
+
+#[test]
+fn selection_is_highlighted_until_move() {
+    let mut app = app_with_highlights();
+    click(&mut app, Some("E2"));
+    assert_eq!(
+        squares_with::<SelectedMarker>(&mut app),
+        vec![Position::from_str("E2")]
+    );
+    assert_eq!(
+        squares_with::<PossibleMoveHighlight>(&mut app),
+        vec![Position::from_str("E3"), Position::from_str("E4")]
+    );
+
+    click(&mut app, Some("E4"));
+    assert!(squares_with::<SelectedMarker>(&mut app).is_empty());
+    assert!(squares_with::<PossibleMoveHighlight>(&mut app).is_empty());
+}
+
+#[test]
+fn clicking_opponent_piece_selects_nothing() {
+    let mut app = app();
+    click(&mut app, Some("E7"));
+    let game = app.world().resource::<ChessGame>();
+    assert_eq!(game.selected_tile, None);
+    assert!(game.possible_moves.is_empty());
+}
+
+#[test]
+fn clicking_outside_keeps_selection() {
+    let mut app = app();
+    click(&mut app, Some("E2"));
+    click(&mut app, None);
+    let game = app.world().resource::<ChessGame>();
+    assert_eq!(game.selected_tile, Some(Position::from_str("E2")));
+    assert_eq!(game.possible_moves.len(), 2);
+    assert_eq!(game.game.active_color(), Color::White);
+}
+
+#[test]
+fn invalid_destination_deselects_without_moving() {
+    let mut app = app();
+    play(&mut app, &[("E2", "E5")]);
+
+    let game = app.world().resource::<ChessGame>();
+    assert_eq!(game.selected_tile, None);
+    assert_eq!(game.game.active_color(), Color::White);
+    assert!(has_marker(&mut app, "E2"));
+    assert!(!has_marker(&mut app, "E5"));
+}
+
+#[derive(Resource, Default)]
+struct MoveErrors(Vec<MoveError>);
+
+#[test]
+fn invalid_destination_is_explained() {
+    let mut app = app();
+    app.init_resource::<MoveErrors>().add_observer(
+        |event: On<IllegalMoveEvent>, mut errors: ResMut<MoveErrors>| {
+            errors.0.push(event.error);
+        },
+    );
+    play(&mut app, &[("E2", "E5"), ("D1", "D3")]);
+
+    assert_eq!(
+        app.world().resource::<MoveErrors>().0,
+        vec![MoveError::Unreachable, MoveError::Unreachable]
+    );
+}
+
+#[test]
+fn move_updates_game_and_markers() {
+    let mut app = app();
+    play(&mut app, &[("E2", "E4")]);
+
+    let game = app.world().resource::<ChessGame>();
+    assert_eq!(game.selected_tile, None);
+    assert_eq!(game.game.active_color(), Color::Black);
+    assert_eq!(
+        game.game
+            .piece_at(Position::from_str("E4"))
+            .map(|piece| piece.piece_type),
+        Some(PieceType::Pawn)
+    );
+    assert!(has_marker(&mut app, "E4"));
+    assert!(!has_marker(&mut app, "E2"));
+    assert_eq!(markers(&mut app).len(), 32);
+}
+
+#[test]
+fn played_moves_are_recorded() {
+    let mut app = app();
+    play(&mut app, &[("E2", "E4"), ("E7", "E5"), ("G1", "F3")]);
+
+    let moves: Vec<String> = app
+        .world()
+        .resource::<ChessGame>()
+        .moves()
+        .iter()
+        .map(|mov| mov.uci())
+        .collect();
+    assert_eq!(moves, vec!["e2e4", "e7e5", "g1f3"]);
+}
+
+#[test]
+fn capture_removes_marker() {
+    let mut app = app();
+    play(&mut app, &[("E2", "E4"), ("D7", "D5"), ("E4", "D5")]);
+
+    assert_eq!(markers(&mut app).len(), 31);
+    assert!(has_marker(&mut app, "D5"));
+    assert!(!has_marker(&mut app, "E4"));
+}
+
+#[test]
+fn castling_moves_king_and_rook_markers() {
+    let mut app = app();
+    play(
+        &mut app,
+        &[
+            ("E2", "E4"),
+            ("E7", "E5"),
+            ("G1", "F3"),
+            ("B8", "C6"),
+            ("F1", "C4"),
+            ("G8", "F6"),
+            ("E1", "G1"),
+        ],
+    );
+
+    let game = app.world().resource::<ChessGame>();
+    assert_eq!(
+        game.game
+            .piece_at(Position::from_str("G1"))
+            .map(|piece| piece.piece_type),
+        Some(PieceType::King)
+    );
+    assert_eq!(
+        game.game
+            .piece_at(Position::from_str("F1"))
+            .map(|piece| piece.piece_type),
+        Some(PieceType::Rook)
+    );
+    assert!(has_marker(&mut app, "G1"));
+    assert!(has_marker(&mut app, "F1"));
+    assert!(!has_marker(&mut app, "E1"));
+    assert!(!has_marker(&mut app, "H1"));
+}
+
+#[test]
+fn en_passant_removes_marker_of_passed_pawn() {
+    let mut app = app();
+    play(
+        &mut app,
+        &[
+            ("E2", "E4"),
+            ("A7", "A6"),
+            ("E4", "E5"),
+            ("D7", "D5"),
+            ("E5", "D6"),
+        ],
+    );
+
+    let game = app.world().resource::<ChessGame>();
+    assert!(game.game.piece_at(Position::from_str("D5")).is_none());
+    assert_eq!(markers(&mut app).len(), 31);
+    assert!(has_marker(&mut app, "D6"));
+    assert!(!has_marker(&mut app, "D5"));
+}
+
+#[test]
+fn promotion_marks_model_for_replacement() {
+    let mut app = app_with(
+        GameBuilder::empty()
+            .with(Piece::new(PieceType::King, Color::White), "e1")
+            .with(Piece::new(PieceType::Pawn, Color::White), "b7")
+            .with(Piece::new(PieceType::Rook, Color::Black), "a8")
+            .with(Piece::new(PieceType::King, Color::Black), "h8")
+            .build(),
+    );
+    play(&mut app, &[("B7", "A8")]);
+
+    let game = app.world().resource::<ChessGame>();
+    assert_eq!(
+        game.game
+            .piece_at(Position::from_str("A8"))
+            .map(|piece| piece.piece_type),
+        Some(PieceType::Queen)
+    );
+    assert_eq!(
+        markers(&mut app),
+        vec![
+            Position::from_str("A8"),
+            Position::from_str("E1"),
+            Position::from_str("H8")
+        ]
+    );
+    let promoted: Vec<PieceType> = app
+        .world_mut()
+        .query::<&Promoted>()
+        .iter(app.world())
+        .map(|promoted| promoted.piece.piece_type)
+        .collect();
+    assert_eq!(promoted, vec![PieceType::Queen]);
+}
+
+fn console_command(app: &mut App, line: &str) -> String {
+    app.world_mut().trigger(ConsoleCommandEvent {
+        line: line.to_string(),
+    });
+    app.update();
+    app.world()
+        .resource::<Console>()
+        .output
+        .last()
+        .cloned()
+        .unwrap_or_default()
+}
+
+#[test]
+fn console_moves_and_takes_back() {
+    let mut app = app();
+    app.init_resource::<Console>()
+        .init_resource::<LogBuffer>()
+        .add_observer(console::run_console_command);
+
+    assert_eq!(console_command(&mut app, "move e2e4"), "Played e2e4");
+    assert_eq!(
+        console_command(&mut app, "move e2e4"),
+        "Illegal move: there is no piece on this square"
+    );
+    assert!(has_marker(&mut app, "E4"));
+    assert_eq!(
+        app.world().resource::<ChessGame>().game.active_color(),
+        Color::Black
+    );
+
+    assert_eq!(console_command(&mut app, "undo"), "Took the last move back");
+    let game = app.world().resource::<ChessGame>();
+    assert_eq!(game.game, Game::new());
+    assert!(game.moves().is_empty());
+    assert_eq!(console_command(&mut app, "undo"), "No move to take back");
+    assert_eq!(console_command(&mut app, "perft 2"), "400 positions");
+
+    // a different move after taking one back replaces it in the played moves
+    console_command(&mut app, "move d2d4");
+    let moves: Vec<String> = app
+        .world()
+        .resource::<ChessGame>()
+        .moves()
+        .iter()
+        .map(|mov| mov.uci())
+        .collect();
+    assert_eq!(moves, ["d2d4"]);
+}
+
+#[test]
+fn console_move_clears_selection() {
+    let mut app = app();
+    app.init_resource::<Console>()
+        .init_resource::<LogBuffer>()
+        .add_observer(console::run_console_command);
+
+    click(&mut app, Some("E2"));
+    assert_eq!(console_command(&mut app, "move g1f3"), "Played g1f3");
+    let game = app.world().resource::<ChessGame>();
+    assert_eq!(game.selected_tile, None);
+    assert!(game.possible_moves.is_empty());
+    assert!(has_marker(&mut app, "F3"));
+}
+
+#[test]
+fn console_refuses_deep_perft() {
+    let mut app = app();
+    app.init_resource::<Console>()
+        .init_resource::<LogBuffer>()
+        .add_observer(console::run_console_command);
+
+    assert_eq!(
+        console_command(&mut app, "perft 4"),
+        "Depth 4 would take too long, at most 3"
+    );
+}
+
+#[test]
+fn console_shows_log_messages() {
+    let mut app = app();
+    app.init_resource::<Console>()
+        .init_resource::<LogBuffer>()
+        .add_observer(console::run_console_command);
+    assert_eq!(console_command(&mut app, "log"), "No log messages");
+
+    let subscriber = Registry::default().with(app.world().resource::<LogBuffer>().layer());
+    tracing::subscriber::with_default(subscriber, || {
+        info!("first");
+        console_command(&mut app, "perft 1");
+    });
+    assert_eq!(
+        console_command(&mut app, "log"),
+        "INFO headless_app: first\nINFO chess::console: Console: perft 1 -> 20 positions"
+    );
+}
+
+#[test]
+fn draw_result_is_removed_when_game_is_replaced() {
+    let mut app = app();
+    app.add_observer(draw_claim::remove_draw_result);
+    app.world_mut().spawn(DrawResultPanel {});
+    app.world_mut().trigger(GameReplacedEvent {});
+    app.update();
+
+    let panels = app
+        .world_mut()
+        .query::<&DrawResultPanel>()
+        .iter(app.world())
+        .count();
+    assert_eq!(panels, 0);
+}
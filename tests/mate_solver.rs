@@ -0,0 +1,77 @@
+use chess::gamelogic::{
+    coordinates::Position,
+    game::Game,
+    moves::{Move, MoveRequest},
+    pieces::{Color::*, Piece, PieceType::*},
+    testing::{self, GameBuilder},
+};
+
+fn mov(game: &Game, origin: &str, destination: &str) -> Move {
+    MoveRequest::new(
+        Position::from_str(origin),
+        Position::from_str(destination),
+        None,
+    )
+    .check(game)
+    .unwrap()
+}
+
+#[test]
+fn finds_back_rank_mate_in_one() {
+    let game = GameBuilder::empty()
+        .with(Piece::new(King, White), "g1")
+        .with(Piece::new(Rook, White), "e1")
+        .with(Piece::new(King, Black), "g8")
+        .with(Piece::new(Pawn, Black), "f7")
+        .with(Piece::new(Pawn, Black), "g7")
+        .with(Piece::new(Pawn, Black), "h7")
+        .build();
+    assert_eq!(game.mating_moves(1), vec![mov(&game, "E1", "E8")]);
+
+    let mated = game.perform_move(mov(&game, "E1", "E8")).unwrap();
+    assert!(mated.is_checkmate());
+    assert_eq!(mated.winner(), Some(White));
+}
+
+#[test]
+fn finds_all_key_moves_of_rook_ladder() {
+    let game = GameBuilder::empty()
+        .with(Piece::new(King, White), "e1")
+        .with(Piece::new(Rook, White), "a1")
+        .with(Piece::new(Rook, White), "b2")
+        .with(Piece::new(King, Black), "h8")
+        .build();
+    assert!(game.mating_moves(1).is_empty());
+
+    let key_moves = game.mating_moves(2);
+    assert!(key_moves.contains(&mov(&game, "B2", "B7")));
+    assert!(key_moves.contains(&mov(&game, "A1", "A7")));
+    assert!(!key_moves.contains(&mov(&game, "E1", "E2")));
+}
+
+#[test]
+fn stalemate_is_no_mate() {
+    let game = GameBuilder::empty()
+        .with(Piece::new(King, White), "f7")
+        .with(Piece::new(Queen, White), "b1")
+        .with(Piece::new(King, Black), "h8")
+        .build();
+    assert_eq!(game.mating_moves(1), vec![mov(&game, "B1", "H1")]);
+
+    // leaves the black king without moves, but not in check
+    let stalemate = game.perform_move(mov(&game, "B1", "G6")).unwrap();
+    assert!(stalemate.legal_moves().is_empty());
+    assert!(!stalemate.is_checkmate());
+    assert!(stalemate.is_stalemate());
+    assert_eq!(stalemate.winner(), None);
+}
+
+#[test]
+fn stalemated_side_has_no_mate() {
+    let game = testing::stalemate();
+    assert!(game.legal_moves().is_empty());
+    assert!(!game.is_checkmate());
+    assert!(game.is_stalemate());
+    assert_eq!(game.winner(), None);
+    assert!(game.mating_moves(2).is_empty());
+}
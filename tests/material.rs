@@ -0,0 +1,50 @@
+use chess::gamelogic::{
+    game::Game,
+    pieces::{Color::*, Piece, PieceType::*},
+    testing::GameBuilder,
+};
+
+#[test]
+fn initial_position_is_balanced() {
+    let game = Game::new();
+    assert_eq!(game.material(White), 39);
+    assert_eq!(game.material(Black), 39);
+
+    let imbalance = game.material_imbalance();
+    assert_eq!(imbalance.balance, 0);
+    assert!(imbalance.white_extra.is_empty());
+    assert!(imbalance.black_extra.is_empty());
+    assert_eq!(imbalance.to_string(), "equal material");
+}
+
+#[test]
+fn imbalance_lists_unmatched_pieces() {
+    let game = GameBuilder::kings()
+        .with(Piece::new(Rook, White), "a1")
+        .with(Piece::new(Bishop, White), "c1")
+        .with(Piece::new(Bishop, White), "f1")
+        .with(Piece::new(Rook, Black), "a8")
+        .with(Piece::new(Knight, Black), "b8")
+        .with(Piece::new(Pawn, Black), "a7")
+        .build();
+    assert_eq!(game.material(White), 11);
+    assert_eq!(game.material(Black), 9);
+
+    let imbalance = game.material_imbalance();
+    assert_eq!(imbalance.balance, 2);
+    assert_eq!(imbalance.white_extra, vec![Bishop, Bishop]);
+    assert_eq!(imbalance.black_extra, vec![Knight, Pawn]);
+    assert_eq!(imbalance.to_string(), "bishop pair vs knight+pawn");
+}
+
+#[test]
+fn missing_side_is_described_as_nothing() {
+    let game = GameBuilder::kings()
+        .with(Piece::new(Pawn, Black), "a7")
+        .with(Piece::new(Pawn, Black), "b7")
+        .with(Piece::new(Knight, Black), "g8")
+        .build();
+    let imbalance = game.material_imbalance();
+    assert_eq!(imbalance.balance, -5);
+    assert_eq!(imbalance.to_string(), "nothing vs knight+2 pawns");
+}
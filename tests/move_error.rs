@@ -0,0 +1,114 @@
+use chess::gamelogic::{
+    coordinates::Position,
+    game::Game,
+    moves::{MoveError, MoveRequest},
+    pieces::{Color::*, Piece, PieceType::*},
+    testing::GameBuilder,
+};
+
+fn check(game: &Game, origin: &str, destination: &str) -> Result<(), MoveError> {
+    MoveRequest::new(
+        Position::from_str(origin),
+        Position::from_str(destination),
+        None,
+    )
+    .check(game)
+    .map(|_| ())
+}
+
+#[test]
+fn valid_move_passes() {
+    assert_eq!(check(&Game::new(), "E2", "E4"), Ok(()));
+}
+
+#[test]
+fn basic_errors() {
+    let game = Game::new();
+    assert_eq!(check(&game, "E4", "E5"), Err(MoveError::NoPiece));
+    assert_eq!(check(&game, "E7", "E5"), Err(MoveError::NotYourTurn));
+    assert_eq!(check(&game, "A1", "A2"), Err(MoveError::OwnPiece));
+    assert_eq!(check(&game, "E2", "E5"), Err(MoveError::Unreachable));
+}
+
+#[test]
+fn pinned_piece_cannot_leave_the_king_in_check() {
+    let game = GameBuilder::empty()
+        .with(Piece::new(King, White), "e1")
+        .with(Piece::new(Rook, White), "e2")
+        .with(Piece::new(Rook, Black), "e8")
+        .with(Piece::new(King, Black), "a8")
+        .build();
+    assert_eq!(check(&game, "E2", "D2"), Err(MoveError::KingInCheck));
+    assert_eq!(check(&game, "E2", "E5"), Ok(()));
+}
+
+#[test]
+fn castling_errors() {
+    // white can castle on both sides once the rook on H1 is added
+    let without_rook = GameBuilder::empty()
+        .with(Piece::new(King, White), "e1")
+        .with(Piece::new(Rook, White), "a1")
+        .with(Piece::new(King, Black), "e8");
+    let castling = without_rook.clone().with(Piece::new(Rook, White), "h1");
+    assert_eq!(check(&castling.clone().build(), "E1", "G1"), Ok(()));
+
+    let blocked = castling
+        .clone()
+        .with(Piece::new(Knight, White), "b1")
+        .build();
+    assert_eq!(
+        check(&blocked, "E1", "C1"),
+        Err(MoveError::CastlingBlocked(Position::from_str("B1")))
+    );
+
+    let attacked = castling.clone().with(Piece::new(Rook, Black), "f8").build();
+    assert_eq!(
+        check(&attacked, "E1", "G1"),
+        Err(MoveError::CastlingThroughCheck(Position::from_str("F1")))
+    );
+
+    let in_check = castling.clone().with(Piece::new(Rook, Black), "e5").build();
+    assert_eq!(
+        check(&in_check, "E1", "G1"),
+        Err(MoveError::CastlingOutOfCheck)
+    );
+
+    let destination_attacked = castling.with(Piece::new(Rook, Black), "g5").build();
+    assert_eq!(
+        check(&destination_attacked, "E1", "G1"),
+        Err(MoveError::KingInCheck)
+    );
+
+    assert_eq!(
+        check(&without_rook.build(), "E1", "G1"),
+        Err(MoveError::RookMoved)
+    );
+}
+
+#[test]
+fn moved_king_cannot_castle() {
+    let game = GameBuilder::empty()
+        .with(Piece::new(King, White), "e1")
+        .with(Piece::new(Rook, White), "h1")
+        .with(Piece::new(King, Black), "e8")
+        .build();
+    let game = [("E1", "E2"), ("E8", "D8"), ("E2", "E1"), ("D8", "E8")]
+        .iter()
+        .fold(game, |game, &(origin, destination)| {
+            game.perform_move_request(MoveRequest::new(
+                Position::from_str(origin),
+                Position::from_str(destination),
+                None,
+            ))
+            .unwrap()
+        });
+    assert_eq!(check(&game, "E1", "G1"), Err(MoveError::KingMoved));
+}
+
+#[test]
+fn explanation_names_the_square() {
+    assert_eq!(
+        MoveError::CastlingThroughCheck(Position::from_str("F1")).to_string(),
+        "castling blocked: square F1 is attacked"
+    );
+}
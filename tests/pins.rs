@@ -0,0 +1,78 @@
+use chess::gamelogic::{
+    coordinates::Position,
+    game::{Game, Pin},
+    moves,
+    pieces::{Color::*, Piece, PieceType::*},
+    testing::{GameBuilder, square_at},
+};
+
+fn destinations(game: &Game, origin: &str) -> Vec<Position> {
+    let mut destinations = moves::valid_destinations(square_at(origin), game);
+    destinations.sort_by_key(|pos| (pos.x, pos.y));
+    destinations
+}
+
+#[test]
+fn finds_pins_along_lines_and_diagonals() {
+    let game = GameBuilder::empty()
+        .with(Piece::new(King, White), "e1")
+        .with(Piece::new(Knight, White), "e3")
+        .with(Piece::new(Rook, Black), "e7")
+        .with(Piece::new(Bishop, White), "c3")
+        .with(Piece::new(Queen, Black), "a5")
+        // a rook does not pin along a diagonal
+        .with(Piece::new(Pawn, White), "f2")
+        .with(Piece::new(Rook, Black), "h4")
+        .with(Piece::new(King, Black), "h8")
+        .build();
+    let mut pins = game.pins(White);
+    pins.sort_by_key(|pin| pin.pinned.x);
+    assert_eq!(
+        pins,
+        vec![
+            Pin {
+                pinned: square_at("c3"),
+                pinner: square_at("a5"),
+            },
+            Pin {
+                pinned: square_at("e3"),
+                pinner: square_at("e7"),
+            },
+        ]
+    );
+    assert!(game.pinned_pieces(Black).is_empty());
+}
+
+#[test]
+fn two_pieces_in_between_are_not_pinned() {
+    let game = GameBuilder::empty()
+        .with(Piece::new(King, White), "e1")
+        .with(Piece::new(Knight, White), "e3")
+        .with(Piece::new(Pawn, Black), "e5")
+        .with(Piece::new(Rook, Black), "e7")
+        .with(Piece::new(King, Black), "h8")
+        .build();
+    assert!(game.pinned_pieces(White).is_empty());
+}
+
+#[test]
+fn pinned_piece_moves_only_along_the_pin() {
+    let game = GameBuilder::empty()
+        .with(Piece::new(King, White), "e1")
+        .with(Piece::new(Rook, White), "e3")
+        .with(Piece::new(Knight, White), "d2")
+        .with(Piece::new(Rook, Black), "e6")
+        .with(Piece::new(Bishop, Black), "b4")
+        .with(Piece::new(King, Black), "h8")
+        .build();
+    assert_eq!(
+        destinations(&game, "e3"),
+        vec![
+            square_at("e2"),
+            square_at("e4"),
+            square_at("e5"),
+            square_at("e6")
+        ]
+    );
+    assert!(destinations(&game, "d2").is_empty());
+}
@@ -0,0 +1,61 @@
+use std::collections::HashSet;
+
+use chess::gamelogic::{
+    game::{CastlingRights, Game},
+    pieces::{Color::*, Piece, PieceType::*},
+    testing::{GameBuilder, play},
+};
+
+#[test]
+fn transpositions_are_equal() {
+    let knights_first = play(&Game::new(), &[("g1", "f3"), ("g8", "f6"), ("b1", "c3")]);
+    let queenside_first = play(&Game::new(), &[("b1", "c3"), ("g8", "f6"), ("g1", "f3")]);
+    assert_eq!(knights_first, queenside_first);
+
+    let positions: HashSet<Game> = [knights_first, queenside_first, Game::new()].into();
+    assert_eq!(positions.len(), 2);
+}
+
+#[test]
+fn side_to_move_and_castling_rights_matter() {
+    assert_ne!(
+        Game::new(),
+        play(&Game::new(), &[("g1", "f3"), ("g8", "f6"), ("f3", "g1")])
+    );
+
+    let kings_and_rooks = GameBuilder::empty()
+        .with(Piece::new(King, White), "e1")
+        .with(Piece::new(Rook, White), "h1")
+        .with(Piece::new(King, Black), "e8");
+    assert_ne!(
+        kings_and_rooks.clone().build(),
+        kings_and_rooks
+            .castling_rights(CastlingRights::NONE)
+            .build()
+    );
+}
+
+#[test]
+fn en_passant_only_matters_if_it_is_possible() {
+    // no black pawn can capture the pawn on E4
+    assert_eq!(
+        play(
+            &Game::new(),
+            &[
+                ("e2", "e4"),
+                ("g8", "f6"),
+                ("g1", "f3"),
+                ("f6", "g8"),
+                ("f3", "g1")
+            ]
+        ),
+        play(&Game::new(), &[("e2", "e4")]),
+    );
+
+    let moves = [("e2", "e4"), ("d7", "d5"), ("e4", "e5"), ("f7", "f5")];
+    let capturable = play(&Game::new(), &moves);
+    // the same placement, but the chance to capture en passant is gone
+    let knight_shuffle = [("g1", "f3"), ("g8", "f6"), ("f3", "g1"), ("f6", "g8")];
+    let not_capturable = play(&Game::new(), &[moves.as_slice(), &knight_shuffle].concat());
+    assert_ne!(capturable, not_capturable);
+}
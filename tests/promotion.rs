@@ -0,0 +1,171 @@
+use chess::gamelogic::{
+    game::{CastlingRights, Game},
+    moves::{Move, MoveRequest},
+    pieces::{Color::*, Piece, PieceType},
+    testing::{GameBuilder, square_at},
+};
+
+/// Builds a position from its ranks, from the eighth down to the first, written like the board
+/// part of FEN without the digits: `.` is an empty square, uppercase letters are white pieces.
+/// Pawns off their initial rank count as moved.
+fn position(ranks: [&str; 8]) -> GameBuilder {
+    let mut builder = GameBuilder::empty();
+    for (row, rank) in ranks.iter().enumerate() {
+        let y = 7 - row as u8;
+        for (x, letter) in rank.chars().enumerate() {
+            let piece_type = match letter.to_ascii_lowercase() {
+                'k' => PieceType::King,
+                'q' => PieceType::Queen,
+                'r' => PieceType::Rook,
+                'b' => PieceType::Bishop,
+                'n' => PieceType::Knight,
+                'p' => PieceType::Pawn,
+                _ => continue,
+            };
+            let color = if letter.is_ascii_uppercase() {
+                White
+            } else {
+                Black
+            };
+            let square = format!("{}{}", (b'a' + x as u8) as char, y + 1);
+            let initial_rank = match color {
+                White => 1,
+                Black => 6,
+            };
+            builder = if piece_type == PieceType::Pawn && y != initial_rank {
+                builder.with_moved(Piece::new(piece_type, color), &square)
+            } else {
+                builder.with(Piece::new(piece_type, color), &square)
+            };
+        }
+    }
+    builder
+}
+
+fn promotions(game: &Game, origin: &str) -> Vec<(String, PieceType)> {
+    let mut promotions: Vec<(String, PieceType)> = game
+        .legal_moves()
+        .into_iter()
+        .filter_map(|mov| match mov {
+            Move::Promotion(promotion) if promotion.origin == square_at(origin) => Some((
+                promotion.destination.to_string(),
+                promotion.new_piece.piece_type,
+            )),
+            _ => None,
+        })
+        .collect();
+    promotions.sort_by_key(|(destination, piece_type)| (destination.clone(), *piece_type as u8));
+    promotions
+}
+
+#[test]
+fn pawn_promotes_to_all_four_pieces_also_when_capturing() {
+    let game = position([
+        "...nk...", "..P.....", "........", "........", "........", "........", "........",
+        "....K...",
+    ])
+    .build();
+    let mut expected = vec![];
+    for destination in ["C8", "D8"] {
+        for piece_type in [
+            PieceType::Queen,
+            PieceType::Rook,
+            PieceType::Bishop,
+            PieceType::Knight,
+        ] {
+            expected.push((destination.to_string(), piece_type));
+        }
+    }
+    assert_eq!(promotions(&game, "c7"), expected);
+}
+
+#[test]
+fn request_without_piece_promotes_to_queen() {
+    let game = position([
+        "....k...", "P.......", "........", "........", "........", "........", "........",
+        "....K...",
+    ])
+    .build();
+    let queen = game
+        .perform_move_request(MoveRequest::new(square_at("a7"), square_at("a8"), None))
+        .unwrap();
+    assert_eq!(
+        queen
+            .piece_at(square_at("a8"))
+            .map(|piece| piece.piece_type),
+        Some(PieceType::Queen)
+    );
+    assert!(queen.is_king_in_check(Black));
+
+    let knight = game
+        .perform_move_request(MoveRequest::new(
+            square_at("a7"),
+            square_at("a8"),
+            Some(Piece::new(PieceType::Knight, White)),
+        ))
+        .unwrap();
+    assert_eq!(
+        knight
+            .piece_at(square_at("a8"))
+            .map(|piece| piece.piece_type),
+        Some(PieceType::Knight)
+    );
+    assert!(knight.piece_at(square_at("a7")).is_none());
+}
+
+#[test]
+fn uci_names_round_trip() {
+    let game = position([
+        "....k...", "P.......", "........", "........", "........", "........", "........",
+        "....K...",
+    ])
+    .build();
+    let mut names: Vec<String> = game.legal_moves().iter().map(Move::uci).collect();
+    names.sort();
+    assert_eq!(
+        names[..4],
+        ["a7a8b", "a7a8n", "a7a8q", "a7a8r"].map(String::from)
+    );
+    for name in names {
+        let move_req = MoveRequest::parse(&name, White).unwrap();
+        assert_eq!(move_req.to_move(&game).map(|mov| mov.uci()), Some(name));
+    }
+    assert_eq!(MoveRequest::parse("a7a8k", White), None);
+    assert_eq!(MoveRequest::parse("a7", White), None);
+}
+
+#[test]
+fn perft_with_promotions_and_castling() {
+    // "position 4" of the chess programming wiki
+    let game = position([
+        "r...k..r", "Pppp.ppp", ".b...nbN", "nP......", "BBP.P...", "q....N..", "Pp.P..PP",
+        "R..Q.RK.",
+    ])
+    .castling_rights(CastlingRights {
+        black_kingside: true,
+        black_queenside: true,
+        ..CastlingRights::NONE
+    })
+    .build();
+    assert_eq!(game.perft(1), 6);
+    assert_eq!(game.perft(2), 264);
+    assert_eq!(game.perft(3), 9467);
+}
+
+#[test]
+fn perft_with_promotion_captures() {
+    // "position 5" of the chess programming wiki
+    let game = position([
+        "rnbq.k.r", "pp.Pbppp", "..p.....", "........", "..B.....", "........", "PPP.NnPP",
+        "RNBQK..R",
+    ])
+    .castling_rights(CastlingRights {
+        white_kingside: true,
+        white_queenside: true,
+        ..CastlingRights::NONE
+    })
+    .build();
+    assert_eq!(game.perft(1), 44);
+    assert_eq!(game.perft(2), 1486);
+    assert_eq!(game.perft(3), 62379);
+}
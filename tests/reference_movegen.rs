@@ -0,0 +1,111 @@
+//! Differential test of the move generator against the shakmaty crate.
+//!
+//! Random games are played from the initial position in both implementations in lockstep. In
+//! every position, the sets of legal moves have to match.
+
+use std::collections::BTreeMap;
+
+use chess::gamelogic::{coordinates::Position, game::Game, moves::Move, pieces::PieceType};
+use shakmaty::{CastlingMode, Chess, Position as _};
+
+const GAMES: u64 = 100;
+const MAX_PLIES: usize = 80;
+
+/// Minimal xorshift generator, so every failure can be reproduced from its seed.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+fn square(pos: Position) -> String {
+    format!("{}{}", (b'a' + pos.x) as char, pos.y + 1)
+}
+
+fn uci(mov: &Move) -> String {
+    match mov {
+        Move::NormalMove(normal_move) => {
+            format!(
+                "{}{}",
+                square(normal_move.origin),
+                square(normal_move.destination)
+            )
+        }
+        Move::EnPassante(en_passante) => {
+            format!(
+                "{}{}",
+                square(en_passante.origin),
+                square(en_passante.destination)
+            )
+        }
+        Move::Castling(castling) => format!(
+            "{}{}",
+            square(castling.king_origin),
+            square(castling.king_destination)
+        ),
+        Move::Promotion(promotion) => {
+            let piece = match promotion.new_piece.piece_type {
+                PieceType::Queen => 'q',
+                PieceType::Rook => 'r',
+                PieceType::Bishop => 'b',
+                PieceType::Knight => 'n',
+                PieceType::King | PieceType::Pawn => unreachable!(),
+            };
+            format!(
+                "{}{}{}",
+                square(promotion.origin),
+                square(promotion.destination),
+                piece
+            )
+        }
+    }
+}
+
+fn legal_moves(game: &Game) -> BTreeMap<String, Move> {
+    game.legal_moves()
+        .into_iter()
+        .map(|mov| (uci(&mov), mov))
+        .collect()
+}
+
+fn reference_moves(position: &Chess) -> BTreeMap<String, shakmaty::Move> {
+    position
+        .legal_moves()
+        .into_iter()
+        .map(|mov| (mov.to_uci(CastlingMode::Standard).to_string(), mov))
+        .collect()
+}
+
+#[test]
+fn legal_moves_match_reference() {
+    for seed in 1..=GAMES {
+        let mut rng = Rng(seed);
+        let mut game = Game::new();
+        let mut reference = Chess::default();
+        let mut history = vec![];
+
+        for _ in 0..MAX_PLIES {
+            let moves = legal_moves(&game);
+            let expected = reference_moves(&reference);
+            assert_eq!(
+                moves.keys().collect::<Vec<_>>(),
+                expected.keys().collect::<Vec<_>>(),
+                "seed {seed}, after moves {history:?}"
+            );
+            if moves.is_empty() {
+                break;
+            }
+
+            let index = (rng.next() % moves.len() as u64) as usize;
+            let (name, mov) = moves.into_iter().nth(index).unwrap();
+            game = game.perform_move(mov).unwrap();
+            reference.play_unchecked(&expected[&name]);
+            history.push(name);
+        }
+    }
+}
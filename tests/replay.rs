@@ -0,0 +1,71 @@
+use chess::gamelogic::{
+    coordinates::Position,
+    game::{Game, ReplayError, ReplayErrorKind},
+    moves::MoveRequest,
+    pieces::{Color, PieceType},
+};
+
+fn requests(moves: &[(&str, &str)]) -> Vec<MoveRequest> {
+    moves
+        .iter()
+        .map(|&(origin, destination)| {
+            MoveRequest::new(
+                Position::from_str(origin),
+                Position::from_str(destination),
+                None,
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn replays_valid_moves() {
+    let game = Game::from_moves(&requests(&[("E2", "E4"), ("E7", "E5"), ("G1", "F3")])).unwrap();
+    assert_eq!(game.active_color(), Color::Black);
+    assert_eq!(
+        game.piece_at(Position::from_str("F3"))
+            .map(|piece| piece.piece_type),
+        Some(PieceType::Knight)
+    );
+    assert!(game.piece_at(Position::from_str("G1")).is_none());
+}
+
+#[test]
+fn empty_list_is_initial_position() {
+    let game = Game::from_moves(&[]).unwrap();
+    assert_eq!(game.active_color(), Color::White);
+    assert_eq!(game.perft(1), 20);
+}
+
+#[test]
+fn reports_first_illegal_move() {
+    let moves = requests(&[("E2", "E4"), ("E7", "E5"), ("E4", "E5"), ("A2", "A3")]);
+    assert_eq!(
+        Game::from_moves(&moves).unwrap_err(),
+        ReplayError {
+            index: 2,
+            request: moves[2],
+            kind: ReplayErrorKind::Illegal,
+        }
+    );
+}
+
+#[test]
+fn reports_empty_origin() {
+    let moves = requests(&[("E2", "E4"), ("E6", "E5")]);
+    let error = Game::from_moves(&moves).unwrap_err();
+    assert_eq!(error.index, 1);
+    assert_eq!(error.kind, ReplayErrorKind::EmptyOrigin);
+    assert_eq!(
+        error.to_string(),
+        "move 2 (E6-E5): no piece on the origin square"
+    );
+}
+
+#[test]
+fn reports_move_out_of_turn() {
+    let moves = requests(&[("E2", "E4"), ("D2", "D4")]);
+    let error = Game::from_moves(&moves).unwrap_err();
+    assert_eq!(error.index, 1);
+    assert_eq!(error.kind, ReplayErrorKind::WrongColor);
+}
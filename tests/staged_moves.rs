@@ -0,0 +1,72 @@
+use chess::gamelogic::{
+    game::Game,
+    moves::Move,
+    pieces::{Color::*, Piece, PieceType::*},
+    testing::{GameBuilder, square_at},
+};
+
+fn destinations(moves: &[Move]) -> Vec<String> {
+    let mut destinations: Vec<String> = moves
+        .iter()
+        .map(|mov| format!("{}-{}", mov.origin(), mov.destination()))
+        .collect();
+    destinations.sort();
+    destinations
+}
+
+#[test]
+fn stages_split_the_legal_moves() {
+    let game = Game::new();
+    assert!(game.captures().is_empty());
+    assert_eq!(game.quiet_moves().len(), 20);
+    assert!(game.evasions().is_empty());
+
+    let game = GameBuilder::empty()
+        .with(Piece::new(King, White), "a1")
+        .with(Piece::new(Rook, White), "d1")
+        .with(Piece::new(Knight, Black), "d5")
+        .with(Piece::new(Pawn, Black), "a2")
+        .with(Piece::new(King, Black), "h8")
+        .build();
+    assert_eq!(destinations(&game.captures()), vec!["A1-A2", "D1-D5"]);
+    assert_eq!(
+        game.captures().len() + game.quiet_moves().len(),
+        game.legal_moves().len()
+    );
+    assert!(game.quiet_moves().iter().all(|mov| !mov.is_capture()));
+}
+
+#[test]
+fn evasions_leave_check() {
+    let game = GameBuilder::empty()
+        .with(Piece::new(King, White), "e1")
+        .with(Piece::new(Rook, White), "a2")
+        .with(Piece::new(Rook, Black), "e8")
+        .with(Piece::new(King, Black), "h8")
+        .build();
+    let evasions = game.evasions();
+    assert_eq!(
+        destinations(&evasions),
+        vec!["A2-E2", "E1-D1", "E1-D2", "E1-F1", "E1-F2"]
+    );
+    assert!(
+        evasions
+            .iter()
+            .all(|mov| !game.perform_move(*mov).unwrap().is_king_in_check(White))
+    );
+}
+
+#[test]
+fn double_check_is_evaded_by_the_king() {
+    let game = GameBuilder::empty()
+        .with(Piece::new(King, White), "e1")
+        .with(Piece::new(Rook, White), "a8")
+        .with(Piece::new(Rook, Black), "e8")
+        .with(Piece::new(Knight, Black), "d3")
+        .with(Piece::new(King, Black), "h8")
+        .build();
+    assert_eq!(game.checkers(White).len(), 2);
+    let evasions = game.evasions();
+    assert!(!evasions.is_empty());
+    assert!(evasions.iter().all(|mov| mov.origin() == square_at("e1")));
+}
@@ -0,0 +1,24 @@
+use chess::{gamelogic::pieces::PieceType, tuning::Tuning};
+
+#[test]
+fn file_overrides_given_values_only() {
+    let tuning = Tuning::BUILT_IN.updated(
+        "# comment\n\
+         move_speed 20\n\
+         highlight_queen   4.5\n\
+         lift_height high\n\
+         unknown_name 3\n",
+    );
+    assert_eq!(tuning.move_speed, 20.);
+    assert_eq!(tuning.highlight_height(Some(PieceType::Queen)), 4.5);
+    assert_eq!(tuning.lift_height, Tuning::BUILT_IN.lift_height);
+    assert_eq!(
+        tuning.highlight_height(None),
+        Tuning::BUILT_IN.highlight_empty
+    );
+}
+
+#[test]
+fn shipped_file_matches_built_in_values() {
+    assert_eq!(Tuning::default(), Tuning::BUILT_IN);
+}